@@ -0,0 +1,134 @@
+//! an access log of a web service, lines in the combined format of Apache in
+//! a rotated file of their own, apart from the records of the application
+
+use super::{Log2, RotatingFile};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// a request for `AccessLog::write()`, the empty fields are logged as `-`
+#[derive(Debug, Clone, Default)]
+pub struct Access {
+    /// the address of the client
+    pub remote: String,
+    /// the authenticated user
+    pub user: String,
+    pub method: String,
+    pub path: String,
+    pub protocol: String,
+    pub status: u16,
+    /// bytes of the response body
+    pub bytes: u64,
+    pub referer: String,
+    pub user_agent: String,
+    /// when the request came in, now if none
+    pub time: Option<SystemTime>,
+}
+
+/// the access log, shared by the handlers, every line is written through at once
+pub struct AccessLog {
+    file: Mutex<RotatingFile>,
+}
+
+impl AccessLog {
+    /// the file with the defaults of `log2::open()`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        Self::with(super::open(path))
+    }
+
+    /// the file of the builder with its size, rotation, naming and compression
+    pub fn with(logger: Log2) -> Result<Self, std::io::Error> {
+        let file = RotatingFile::with(logger)?;
+        Ok(AccessLog {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// the active file
+    pub fn path(&self) -> std::path::PathBuf {
+        let file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.path().into()
+    }
+
+    /// append the request as
+    /// `host - user [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.1" 200 2326 "referer" "agent"`
+    pub fn write(&self, access: &Access) -> Result<(), std::io::Error> {
+        let line = combined(access);
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())?;
+        file.flush()
+    }
+}
+
+/// the access log at the path, see `AccessLog::with()` for the rotation
+pub fn access(path: impl AsRef<Path>) -> Result<AccessLog, std::io::Error> {
+    AccessLog::open(path)
+}
+
+fn combined(access: &Access) -> String {
+    let request = [&access.method, &access.path, &access.protocol]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let bytes = match access.bytes {
+        0 => "-".to_string(),
+        bytes => bytes.to_string(),
+    };
+    format!(
+        "{} - {} [{}] \"{}\" {} {} \"{}\" \"{}\"\n",
+        field(&access.remote),
+        field(&access.user),
+        clf(access.time.unwrap_or_else(SystemTime::now)),
+        quoted(&request),
+        access.status,
+        bytes,
+        quoted(&access.referer),
+        quoted(&access.user_agent),
+    )
+}
+
+/// a bare field, `-` when empty, spaces and quotes can't break the line
+fn field(text: &str) -> String {
+    match text.is_empty() {
+        true => "-".into(),
+        false => quoted(text).replace(' ', "\\x20"),
+    }
+}
+
+/// the content of a quoted field, escaped like Apache does, `-` when empty
+fn quoted(text: &str) -> String {
+    if text.is_empty() {
+        return "-".into();
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// the local time like `10/Oct/2000:13:55:36 -0700`
+#[cfg(feature = "chrono")]
+fn clf(time: SystemTime) -> String {
+    let time: chrono::DateTime<chrono::Local> = time.into();
+    time.format("%d/%b/%Y:%H:%M:%S %z").to_string()
+}
+
+/// utc without chrono, like `10/Oct/2000:20:55:36 +0000`
+#[cfg(not(feature = "chrono"))]
+fn clf(time: SystemTime) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let [year, month, day, hour, minute, second] = super::naming::civil(time);
+    let month = MONTHS[(month - 1) as usize];
+    format!("{day:02}/{month}/{year:04}:{hour:02}:{minute:02}:{second:02} +0000")
+}
@@ -0,0 +1,103 @@
+//! output of child processes into the log
+
+use log::LevelFilter;
+use std::io::{BufRead, Read};
+use std::process::{Child, Command, Stdio};
+
+/// spawns commands with their stdout and stderr piped into the log, each
+/// line becomes a record with the target of the logger
+pub struct ChildLogger {
+    target: String,
+    level: LevelFilter,
+    stderr: LevelFilter,
+}
+
+impl ChildLogger {
+    /// lines of stdout at info and of stderr at warn
+    pub fn new(target: &str) -> Self {
+        ChildLogger {
+            target: target.to_string(),
+            level: LevelFilter::Info,
+            stderr: LevelFilter::Warn,
+        }
+    }
+
+    /// the level of the stdout lines, `level::Off` discards them
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// the level of the stderr lines, `level::Off` discards them
+    pub fn stderr_level(mut self, level: LevelFilter) -> Self {
+        self.stderr = level;
+        self
+    }
+
+    /// spawn the command, the lines are forwarded by a thread per stream
+    /// until the child closes them, so the last ones may be logged shortly
+    /// after the child has exited
+    pub fn spawn(&self, command: &mut Command) -> Result<Child, std::io::Error> {
+        let mut child = command
+            .stdout(stdio(self.level))
+            .stderr(stdio(self.stderr))
+            .spawn()?;
+        if let (Some(stdout), Some(level)) = (child.stdout.take(), self.level.to_level()) {
+            forward(stdout, self.target.clone(), level)?;
+        }
+        if let (Some(stderr), Some(level)) = (child.stderr.take(), self.stderr.to_level()) {
+            forward(stderr, self.target.clone(), level)?;
+        }
+        Ok(child)
+    }
+}
+
+fn stdio(level: LevelFilter) -> Stdio {
+    match level {
+        LevelFilter::Off => Stdio::null(),
+        _ => Stdio::piped(),
+    }
+}
+
+/// spawn the command with both streams logged at the level, the target is
+/// the name of the program, like `git` for `/usr/bin/git`
+pub fn pipe_child(command: &mut Command, level: LevelFilter) -> Result<Child, std::io::Error> {
+    let program = std::path::Path::new(command.get_program());
+    let target = program.file_stem().unwrap_or(program.as_os_str());
+    ChildLogger::new(&target.to_string_lossy())
+        .level(level)
+        .stderr_level(level)
+        .spawn(command)
+}
+
+/// read lines on a thread of its own, every one becomes a record
+pub(crate) fn forward(
+    stream: impl Read + Send + 'static,
+    target: String,
+    level: log::Level,
+) -> Result<(), std::io::Error> {
+    std::thread::Builder::new()
+        .name(format!("log2-{target}"))
+        .spawn(move || {
+            let mut stream = std::io::BufReader::new(stream);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match stream.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
+                }
+                let text = String::from_utf8_lossy(&line);
+                let text = text.trim_end_matches(['\r', '\n']);
+                log::logger().log(
+                    &log::Record::builder()
+                        .level(level)
+                        .target(&target)
+                        .module_path(Some(&target))
+                        .args(format_args!("{text}"))
+                        .build(),
+                );
+            }
+        })?;
+    Ok(())
+}
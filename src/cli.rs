@@ -0,0 +1,62 @@
+//! logging flags for clap, flatten `Args` into the command of the application
+//!
+//! ```no_run
+//! #[derive(clap::Parser)]
+//! struct Cli {
+//!     #[command(flatten)]
+//!     log: log2::cli::Args,
+//! }
+//! ```
+
+use super::Log2;
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct Args {
+    /// write the log to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<String>,
+
+    /// trace, debug, info, warn, error or off
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    /// number of log files to keep
+    #[arg(long, value_name = "COUNT")]
+    pub log_rotate: Option<usize>,
+
+    /// only log errors
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// log more, -v for debug, -vv for trace
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+}
+
+impl Args {
+    /// the level picked by the flags, `--log-level` wins over `-q` and `-v`
+    pub fn level(&self) -> String {
+        if let Some(level) = &self.log_level {
+            return level.clone();
+        }
+        let level = match (self.quiet, self.verbose) {
+            (true, _) => "error",
+            (false, 0) => "info",
+            (false, 1) => "debug",
+            _ => "trace",
+        };
+        level.into()
+    }
+
+    /// a logger set up by the flags, to file or to stdout
+    pub fn log2(&self) -> Log2 {
+        let mut logger = match &self.log_file {
+            Some(path) => super::open(path),
+            None => super::stdout(),
+        };
+        if let Some(count) = self.log_rotate {
+            logger = logger.rotate(count);
+        }
+        logger.level(self.level())
+    }
+}
@@ -0,0 +1,410 @@
+//! work on the rotated files for `Log2::compress()`, `Log2::on_rotate()` and
+//! the like, on a thread of its own so that the worker goes on writing while
+//! a large file is compressed
+
+use super::{report, Counters, OnRotate, Tx, WORKER};
+use log::Level;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// how the rotated files are compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Codec {
+    /// `log.1.txt.gz`, readable by `zcat` everywhere
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// `log.1.txt.lz4` in the lz4 frame format, much less cpu for a lower ratio
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+#[cfg(any(feature = "gzip", feature = "lz4"))]
+impl Default for Codec {
+    fn default() -> Self {
+        #[cfg(feature = "gzip")]
+        return Codec::Gzip;
+        #[cfg(not(feature = "gzip"))]
+        return Codec::Lz4;
+    }
+}
+
+impl Codec {
+    const ALL: &[Codec] = &[
+        #[cfg(feature = "gzip")]
+        Codec::Gzip,
+        #[cfg(feature = "lz4")]
+        Codec::Lz4,
+    ];
+
+    fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gzip")]
+            Codec::Gzip => "gz",
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => "lz4",
+        }
+    }
+
+    /// compress everything from the reader into the file
+    #[cfg_attr(not(any(feature = "gzip", feature = "lz4")), allow(unused_variables))]
+    fn encode(self, input: &mut impl Read, output: File) -> Result<File, std::io::Error> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Codec::Gzip => {
+                let level = flate2::Compression::default();
+                let mut encoder = flate2::write::GzEncoder::new(output, level);
+                std::io::copy(input, &mut encoder)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => {
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(output);
+                std::io::copy(input, &mut encoder)?;
+                encoder.finish().map_err(std::io::Error::other)
+            }
+        }
+    }
+}
+
+/// work of the background thread
+enum Job {
+    Compress(PathBuf),
+    /// the active file, and its rotated files
+    #[cfg(feature = "bundle")]
+    Bundle(PathBuf, Vec<PathBuf>),
+}
+
+pub(crate) struct Compressor {
+    /// none when the thread only bundles
+    codec: Option<Codec>,
+    /// days until the rotated files go into a tarball
+    #[cfg(feature = "bundle")]
+    bundle: Option<u32>,
+    /// the key of `Log2::encrypt_archives()`
+    #[cfg(feature = "encrypt")]
+    key: Option<[u8; 32]>,
+    /// the callback of `Log2::on_rotate()`
+    notify: Option<OnRotate>,
+    jobs: Option<std::sync::mpsc::Sender<Job>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// held while a file is compressed, the renames of the next rotation wait
+    /// for it, so a file is never moved away under the compression
+    busy: Arc<Mutex<()>>,
+    tx: Tx,
+    counters: Arc<Counters>,
+}
+
+impl Compressor {
+    pub(crate) fn new(codec: Option<Codec>, tx: Tx, counters: Arc<Counters>) -> Self {
+        Compressor {
+            codec,
+            #[cfg(feature = "bundle")]
+            bundle: None,
+            #[cfg(feature = "encrypt")]
+            key: None,
+            notify: None,
+            jobs: None,
+            thread: None,
+            busy: Default::default(),
+            tx,
+            counters,
+        }
+    }
+
+    #[cfg(feature = "bundle")]
+    pub(crate) fn bundle(mut self, days: Option<u32>) -> Self {
+        self.bundle = days;
+        self
+    }
+
+    #[cfg(feature = "encrypt")]
+    pub(crate) fn encrypt(mut self, key: Option<[u8; 32]>) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub(crate) fn notify(mut self, notify: Option<OnRotate>) -> Self {
+        self.notify = notify;
+        self
+    }
+
+    /// the endings the rotated files get, which move along with them
+    pub(crate) fn suffixes(&self) -> Vec<String> {
+        let suffixes: Vec<_> = self
+            .codec
+            .iter()
+            .map(|c| format!(".{}", c.extension()))
+            .collect();
+        #[cfg(feature = "encrypt")]
+        if self.key.is_some() {
+            let suffix = suffixes.first().cloned().unwrap_or_default();
+            return [suffixes, vec![format!("{suffix}.enc")]].concat();
+        }
+        suffixes
+    }
+
+    /// nothing to do for the thread
+    pub(crate) fn idle(&self) -> bool {
+        #[cfg(feature = "bundle")]
+        if self.bundle.is_some() {
+            return false;
+        }
+        !self.archiving() && self.notify.is_none()
+    }
+
+    /// the rotated files are compressed or encrypted
+    pub(crate) fn archiving(&self) -> bool {
+        #[cfg(feature = "encrypt")]
+        if self.key.is_some() {
+            return true;
+        }
+        self.codec.is_some()
+    }
+
+    /// hold off the compression during the renames of a rotation
+    pub(crate) fn pause(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.busy.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// queue a rotated file
+    pub(crate) fn compress(&mut self, path: PathBuf) {
+        if self.archiving() || self.notify.is_some() {
+            self.send(Job::Compress(path));
+        }
+    }
+
+    /// queue the rotated files of the active file to check their age
+    #[cfg(feature = "bundle")]
+    pub(crate) fn gather(&mut self, path: PathBuf, archives: Vec<PathBuf>) {
+        if self.bundle.is_some() {
+            self.send(Job::Bundle(path, archives));
+        }
+    }
+
+    /// the thread starts with the first job
+    fn send(&mut self, job: Job) {
+        if self.jobs.is_none() {
+            let (jobs, rx) = std::sync::mpsc::channel::<Job>();
+            let (codec, busy, notify) = (self.codec, self.busy.clone(), self.notify.clone());
+            #[cfg(feature = "bundle")]
+            let days = self.bundle;
+            #[cfg(feature = "encrypt")]
+            let key = self.key;
+            #[cfg(not(feature = "encrypt"))]
+            let key = None;
+            let (tx, counters) = (self.tx.clone(), self.counters.clone());
+            let thread = std::thread::Builder::new()
+                .name(WORKER.into())
+                .spawn(move || {
+                    for job in rx.iter() {
+                        let pause = busy.lock().unwrap_or_else(|e| e.into_inner());
+                        let (result, path) = match job {
+                            Job::Compress(path) => (archive(&path, codec, key).map(Some), path),
+                            #[cfg(feature = "bundle")]
+                            Job::Bundle(path, archives) => {
+                                let days = days.unwrap_or_default();
+                                (bundle(&path, &archives, days).map(|_| None), path)
+                            }
+                        };
+                        // a slow callback doesn't hold up the next rotation
+                        drop(pause);
+                        match result {
+                            Ok(archived) => {
+                                if let (Some(notify), Some(archived)) = (&notify, archived) {
+                                    notify(&archived);
+                                }
+                            }
+                            // moved on by the next rotation already
+                            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                            Err(e) => {
+                                let message = format!("failed to compress {}: {e}", path.display());
+                                report(&tx, &counters, Level::Warn, message);
+                            }
+                        }
+                    }
+                });
+            match thread {
+                Ok(thread) => {
+                    self.jobs = Some(jobs);
+                    self.thread = Some(thread);
+                }
+                Err(e) => {
+                    let message = format!("failed to start the compression: {e}");
+                    report(&self.tx, &self.counters, Level::Warn, message);
+                    return;
+                }
+            }
+        }
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(job);
+        }
+    }
+}
+
+impl Drop for Compressor {
+    /// finish the queued files before the worker is gone
+    fn drop(&mut self) {
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// the name of a compressed file, `log.1.txt.gz` for `log.1.txt`
+fn name(path: &Path, codec: Codec) -> PathBuf {
+    suffixed(path, &format!(".{}", codec.extension()))
+}
+
+pub(crate) fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    name.into()
+}
+
+/// every ending a rotated file might have
+fn endings() -> Vec<String> {
+    let endings: Vec<_> = Codec::ALL
+        .iter()
+        .map(|c| format!(".{}", c.extension()))
+        .collect();
+    #[cfg(feature = "encrypt")]
+    let endings = {
+        let encrypted = endings.iter().map(|e| format!("{e}.enc"));
+        let encrypted: Vec<_> = encrypted.chain([".enc".to_string()]).collect();
+        [endings, encrypted].concat()
+    };
+    endings
+}
+
+/// the file is compressed or encrypted already
+pub(crate) fn compressed(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    endings()
+        .iter()
+        .any(|ending| path.ends_with(ending.as_str()))
+}
+
+/// the rotated file, or its compressed file once it is gone
+pub(crate) fn existing(path: PathBuf) -> PathBuf {
+    if path.exists() {
+        return path;
+    }
+    endings()
+        .iter()
+        .map(|ending| suffixed(&path, ending))
+        .find(|name| name.exists())
+        .unwrap_or(path)
+}
+
+/// compress a rotated file, then encrypt it, the final file is returned
+fn archive(
+    path: &Path,
+    codec: Option<Codec>,
+    key: Option<[u8; 32]>,
+) -> Result<PathBuf, std::io::Error> {
+    let path = match codec {
+        Some(codec) => {
+            compress(path, codec)?;
+            name(path, codec)
+        }
+        None => path.to_path_buf(),
+    };
+    #[cfg(feature = "encrypt")]
+    if let Some(key) = key {
+        super::crypt::encrypt(&path, &key)?;
+        return Ok(suffixed(&path, ".enc"));
+    }
+    #[cfg(not(feature = "encrypt"))]
+    let _ = key;
+    Ok(path)
+}
+
+/// compress into a temporary file first, the original is removed once the
+/// compressed file is complete
+fn compress(path: &Path, codec: Codec) -> Result<(), std::io::Error> {
+    let name = name(path, codec);
+    let mut temp = name.as_os_str().to_owned();
+    temp.push(".tmp");
+    let result = (|| {
+        let mut input = File::open(path)?;
+        let output = File::create(&temp)?;
+        codec.encode(&mut input, output)?.sync_data()?;
+        std::fs::rename(&temp, &name)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result?;
+    std::fs::remove_file(path)
+}
+
+/// gather the rotated files older than the days into tarballs of their month
+/// next to the active file, `log-2024-06.tar.gz` for `log.txt`, every file
+/// goes into a directory of its time, as the indices repeat
+#[cfg(feature = "bundle")]
+fn bundle(path: &Path, archives: &[PathBuf], days: u32) -> Result<(), std::io::Error> {
+    let age = std::time::Duration::from_secs(u64::from(days) * 24 * 3600);
+    let now = std::time::SystemTime::now();
+    let mut months = std::collections::BTreeMap::<String, Vec<_>>::new();
+    for archive in archives {
+        let archive = existing(archive.clone());
+        let Ok(modified) = std::fs::metadata(&archive).and_then(|meta| meta.modified()) else {
+            continue;
+        };
+        if now.duration_since(modified).unwrap_or_default() < age {
+            continue;
+        }
+        let stamp = super::naming::stamp(modified);
+        let name = archive.file_name().unwrap_or_default().to_string_lossy();
+        let name = format!("{stamp}/{name}");
+        let month = stamp[..7].to_string();
+        months.entry(month).or_default().push((archive, name));
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    for (month, files) in months {
+        let tarball = path.with_file_name(format!("{stem}-{month}.tar.gz"));
+        append(&tarball, &files)?;
+        for (archive, _) in files {
+            std::fs::remove_file(archive)?;
+        }
+    }
+    Ok(())
+}
+
+/// a tarball can't be appended to once compressed, it is rewritten with the
+/// files of before and the new ones
+#[cfg(feature = "bundle")]
+fn append(tarball: &Path, files: &[(PathBuf, String)]) -> Result<(), std::io::Error> {
+    let mut temp = tarball.as_os_str().to_owned();
+    temp.push(".tmp");
+    let result = (|| {
+        let output = File::create(&temp)?;
+        let level = flate2::Compression::default();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(output, level));
+        if let Ok(input) = File::open(tarball) {
+            let mut before = tar::Archive::new(flate2::read::GzDecoder::new(input));
+            for entry in before.entries()? {
+                let mut entry = entry?;
+                let header = entry.header().clone();
+                builder.append(&header, &mut entry)?;
+            }
+        }
+        for (file, name) in files {
+            builder.append_path_with_name(file, name)?;
+        }
+        builder.into_inner()?.finish()?.sync_data()?;
+        std::fs::rename(&temp, tarball)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
@@ -0,0 +1,164 @@
+//! `Log2Config` and `from_config()` for the `config` feature, toml or yaml
+
+#[cfg(any(feature = "gzip", feature = "lz4"))]
+use super::Codec;
+use super::{Format, Log2};
+use serde::Deserialize;
+
+/// logging setup read from a config file, missing keys keep the builder defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Log2Config {
+    /// log file, stdout only when missing
+    pub path: Option<String>,
+    /// maximum size of each file
+    pub size: Option<u64>,
+    /// number of files to keep
+    pub rotate: Option<usize>,
+    pub level: Option<String>,
+    /// mirror to stdout as well, default when there is no file
+    pub tee: Option<bool>,
+    /// show the module of each record
+    pub module: Option<bool>,
+    /// only log these modules and their submodules, or globs like `my_app::*`
+    pub modules: Option<Vec<String>>,
+    /// never log these modules, globs like `h2::*` included
+    pub deny_modules: Option<Vec<String>>,
+    /// full, compact, pretty, json or logfmt
+    pub format: Option<Format>,
+    /// compress the rotated files, needs the gzip or lz4 feature
+    pub compress: Option<bool>,
+    /// gzip or lz4 for `compress`
+    #[cfg(any(feature = "gzip", feature = "lz4"))]
+    pub codec: Option<Codec>,
+}
+
+impl From<Log2Config> for Log2 {
+    fn from(config: Log2Config) -> Log2 {
+        let mut logger = match &config.path {
+            Some(path) => super::open(path),
+            None => super::stdout(),
+        };
+        if let Some(tee) = config.tee {
+            logger = logger.tee(tee);
+        }
+        if let Some(module) = config.module {
+            logger = logger.module(module);
+        }
+        // rotate first, size is ignored for a single file
+        if let Some(count) = config.rotate {
+            logger = logger.rotate(count);
+        }
+        if let Some(size) = config.size {
+            logger = logger.size(size);
+        }
+        if let Some(format) = config.format {
+            logger = logger.preset(format);
+        }
+        if let Some(compress) = config.compress {
+            logger = logger.compress(compress);
+        }
+        #[cfg(any(feature = "gzip", feature = "lz4"))]
+        if let Some(codec) = config.codec {
+            logger = logger.codec(codec);
+        }
+        if let Some(level) = config.level {
+            logger = logger.level(level);
+        }
+        if let Some(modules) = config.deny_modules {
+            logger = logger.deny_modules(modules);
+        }
+        let modules = config.modules.unwrap_or_default();
+        logger.modules = Some(std::sync::Arc::new(std::sync::RwLock::new(modules)));
+        logger
+    }
+}
+
+impl Log2Config {
+    /// parse toml, or yaml when toml fails
+    pub fn parse(text: &str) -> Result<Self, std::io::Error> {
+        match toml::from_str(text) {
+            Ok(config) => Ok(config),
+            Err(toml) => {
+                serde_yaml::from_str(text).map_err(|yaml| invalid(format!("{toml}{yaml}")))
+            }
+        }
+    }
+
+    /// read a config file, the extension picks yaml or toml
+    pub fn load(path: &str) -> Result<Self, std::io::Error> {
+        let text = std::fs::read_to_string(path)?;
+        if path.ends_with(".yaml") || path.ends_with(".yml") {
+            serde_yaml::from_str(&text).map_err(|e| invalid(e.to_string()))
+        } else if path.ends_with(".toml") {
+            toml::from_str(&text).map_err(|e| invalid(e.to_string()))
+        } else {
+            Self::parse(&text)
+        }
+    }
+}
+
+fn invalid(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// a logger configured by a file, or by the config text itself
+pub fn from_config(path_or_str: &str) -> Result<Log2, std::io::Error> {
+    let config = match std::path::Path::new(path_or_str).is_file() {
+        true => Log2Config::load(path_or_str)?,
+        false => Log2Config::parse(path_or_str)?,
+    };
+    Ok(config.into())
+}
+
+/// apply the level, the tee and the modules of the config file whenever it
+/// changes, the parent directory is watched so editors replacing the file work
+#[cfg(feature = "watch")]
+pub(crate) fn watch(
+    path: &str,
+    tx: super::Tx,
+    counters: std::sync::Arc<super::Counters>,
+    modules: Option<super::Modules>,
+) -> Result<notify::RecommendedWatcher, std::io::Error> {
+    use notify::Watcher;
+
+    let file = std::path::PathBuf::from(path);
+    let name = file.file_name().map(|name| name.to_owned());
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_owned(),
+        _ => std::path::PathBuf::from("."),
+    };
+
+    let handler = move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let changed = event.kind.is_modify() || event.kind.is_create();
+        if !changed || !event.paths.iter().any(|p| p.file_name() == name.as_deref()) {
+            return;
+        }
+        let config = match Log2Config::load(&file.to_string_lossy()) {
+            Ok(config) => config,
+            Err(e) => {
+                let message = format!("failed to reload {}: {e}", file.display());
+                super::report(&tx, &counters, log::Level::Error, message);
+                return;
+            }
+        };
+        if let Some(level) = config.level {
+            super::set_level(level);
+        }
+        if let Some(tee) = config.tee {
+            tx.send(super::Action::Tee(tee));
+        }
+        if let Some(Ok(mut modules)) = modules.as_ref().map(|m| m.write()) {
+            *modules = config.modules.unwrap_or_default();
+        }
+    };
+
+    let mut watcher = notify::recommended_watcher(handler).map_err(std::io::Error::other)?;
+    watcher
+        .watch(&dir, notify::RecursiveMode::NonRecursive)
+        .map_err(std::io::Error::other)?;
+    Ok(watcher)
+}
@@ -0,0 +1,15 @@
+//! the console of the browser in place of stdout for wasm builds
+
+use log::Level;
+use web_sys::wasm_bindgen::JsValue;
+
+/// a tee line at the console method of its level, so the browser can filter
+pub(crate) fn write(level: Level, line: &str) {
+    let line = JsValue::from_str(line);
+    match level {
+        Level::Error => web_sys::console::error_1(&line),
+        Level::Warn => web_sys::console::warn_1(&line),
+        Level::Info => web_sys::console::info_1(&line),
+        Level::Debug | Level::Trace => web_sys::console::debug_1(&line),
+    }
+}
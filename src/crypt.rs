@@ -0,0 +1,105 @@
+//! encryption of the rotated files for `Log2::encrypt_archives()`, AES-256-GCM
+//! over chunks, so a large file never has to fit into memory
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"LOG2AES1";
+const CHUNK: usize = 64 * 1024;
+const TAG: usize = 16;
+
+/// the nonce of a chunk: the random prefix of the file, the index of the
+/// chunk and whether it is the last one, so chunks can't be reordered or cut off
+fn nonce(prefix: &[u8; 7], index: u32, last: bool) -> aes_gcm::Nonce<aes_gcm::aead::consts::U12> {
+    let mut nonce = [0; 12];
+    nonce[..7].copy_from_slice(prefix);
+    nonce[7..11].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce.into()
+}
+
+/// read until the buffer is full or the end of the file
+fn fill(input: &mut impl Read, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+    let mut n = 0;
+    while n < buf.len() {
+        match input.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(read) => n += read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+fn invalid() -> std::io::Error {
+    let message = "not an archive of this key, or it was altered";
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// encrypt into `<file>.enc` through a temporary file, the original is
+/// removed once the encrypted file is complete
+pub(crate) fn encrypt(path: &Path, key: &[u8; 32]) -> Result<(), std::io::Error> {
+    let name = super::compress::suffixed(path, ".enc");
+    let temp = super::compress::suffixed(&name, ".tmp");
+    let result = (|| {
+        let cipher = Aes256Gcm::new(key.into());
+        let mut prefix = [0; 7];
+        OsRng.fill_bytes(&mut prefix);
+        let mut input = File::open(path)?;
+        let mut output = std::io::BufWriter::new(File::create(&temp)?);
+        output.write_all(MAGIC)?;
+        output.write_all(&prefix)?;
+        let mut buf = vec![0; CHUNK];
+        for index in 0.. {
+            let n = fill(&mut input, &mut buf)?;
+            let last = n < CHUNK;
+            let sealed = cipher
+                .encrypt(&nonce(&prefix, index, last), &buf[..n])
+                .map_err(|_| invalid())?;
+            output.write_all(&sealed)?;
+            if last {
+                break;
+            }
+        }
+        output.into_inner()?.sync_data()?;
+        std::fs::rename(&temp, &name)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result?;
+    std::fs::remove_file(path)
+}
+
+/// the content of a file encrypted by `Log2::encrypt_archives()`, still
+/// compressed for a `.gz.enc` or `.lz4.enc` file
+pub fn decrypt(path: impl AsRef<Path>, key: &[u8; 32]) -> Result<Vec<u8>, std::io::Error> {
+    let cipher = Aes256Gcm::new(key.into());
+    let mut input = std::io::BufReader::new(File::open(path)?);
+    let mut magic = [0; 8];
+    let mut prefix = [0; 7];
+    input.read_exact(&mut magic).map_err(|_| invalid())?;
+    input.read_exact(&mut prefix).map_err(|_| invalid())?;
+    if &magic != MAGIC {
+        return Err(invalid());
+    }
+    let mut content = Vec::new();
+    let mut buf = vec![0; CHUNK + TAG];
+    for index in 0.. {
+        let n = fill(&mut input, &mut buf)?;
+        let last = n < CHUNK + TAG;
+        let chunk = cipher
+            .decrypt(&nonce(&prefix, index, last), &buf[..n])
+            .map_err(|_| invalid())?;
+        content.extend_from_slice(&chunk);
+        if last {
+            break;
+        }
+    }
+    Ok(content)
+}
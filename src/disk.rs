@@ -0,0 +1,68 @@
+//! free space of the volume for `Log2::low_disk()`, checked by the worker
+//! every few seconds so that a full disk is noticed before writes fail
+
+use super::OnLowDisk;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// between two checks of the free space
+const PERIOD: Duration = Duration::from_secs(10);
+
+pub(crate) struct Disk {
+    threshold: u64,
+    pub(crate) hook: Option<OnLowDisk>,
+    checked: Option<Instant>,
+    /// below the threshold at the last check, warn once per shortage
+    low: bool,
+}
+
+impl Disk {
+    pub(crate) fn new(threshold: u64, hook: Option<OnLowDisk>) -> Self {
+        Disk {
+            threshold,
+            hook,
+            checked: None,
+            low: false,
+        }
+    }
+
+    /// the free bytes once they fell below the threshold
+    pub(crate) fn check(&mut self, path: &Path) -> Option<u64> {
+        if self
+            .checked
+            .is_some_and(|checked| checked.elapsed() < PERIOD)
+        {
+            return None;
+        }
+        self.checked = Some(Instant::now());
+        let free = free(path)?;
+        let low = free < self.threshold;
+        let fell = low && !self.low;
+        self.low = low;
+        fell.then_some(free)
+    }
+}
+
+/// bytes available to this process on the volume of the file
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn free(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let dir = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(dir.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// unknown elsewhere, never low
+#[cfg(not(unix))]
+fn free(_path: &Path) -> Option<u64> {
+    None
+}
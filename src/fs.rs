@@ -0,0 +1,267 @@
+//! the filesystem under the rotation, the disk or the `MemoryFs` of
+//! `Log2::memory_fs()` for tests of the retention without touching the disk
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// what the rotation needs of a file
+pub(crate) struct Metadata {
+    pub(crate) len: u64,
+    pub(crate) modified: std::time::SystemTime,
+}
+
+/// the operations of `rotate()`, the naming and the pruning
+pub(crate) trait Fs: Send + Sync {
+    /// the file and its directories, before the worker starts
+    fn create(&self, path: &Path) -> Result<(), std::io::Error>;
+    /// open for appending, created with the permissions if missing
+    fn open(&self, path: &Path, mode: Option<u32>) -> Result<File, std::io::Error>;
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), std::io::Error>;
+    fn remove(&self, path: &Path) -> Result<(), std::io::Error>;
+    fn metadata(&self, path: &Path) -> Result<Metadata, std::io::Error>;
+    /// the files in a directory
+    fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, std::io::Error>;
+
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// the file exists and can be opened for appending, without creating it
+    fn writable(&self, path: &Path) -> bool {
+        self.exists(path)
+    }
+
+    /// the files are in memory, there is nothing for `mode()`, `owner()`,
+    /// the locks of `shared()` and the directories of `template()` to act on
+    fn memory(&self) -> bool {
+        false
+    }
+}
+
+/// an opened file of a `Fs`
+pub(crate) enum File {
+    Disk(std::fs::File),
+    #[cfg(feature = "memory-fs")]
+    Memory(Node),
+}
+
+impl File {
+    /// the file on the disk, none in memory
+    pub(crate) fn disk(&self) -> Option<&std::fs::File> {
+        match self {
+            File::Disk(file) => Some(file),
+            #[cfg(feature = "memory-fs")]
+            File::Memory(_) => None,
+        }
+    }
+
+    pub(crate) fn len(&self) -> Result<u64, std::io::Error> {
+        match self {
+            File::Disk(file) => Ok(file.metadata()?.len()),
+            #[cfg(feature = "memory-fs")]
+            File::Memory(node) => Ok(lock(node).data.len() as u64),
+        }
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
+        match self {
+            File::Disk(file) => file.write(buf),
+            #[cfg(feature = "memory-fs")]
+            File::Memory(node) => {
+                let mut inode = lock(node);
+                inode.data.extend_from_slice(buf);
+                inode.modified = std::time::SystemTime::now();
+                Ok(buf.len())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        match self {
+            File::Disk(file) => file.flush(),
+            #[cfg(feature = "memory-fs")]
+            File::Memory(_) => Ok(()),
+        }
+    }
+}
+
+/// the real filesystem
+pub(crate) struct Disk;
+
+impl Fs for Disk {
+    fn create(&self, path: &Path) -> Result<(), std::io::Error> {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(())
+    }
+
+    fn open(&self, path: &Path, mode: Option<u32>) -> Result<File, std::io::Error> {
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        if let Some(mode) = mode {
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+        Ok(File::Disk(options.open(path)?))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), std::io::Error> {
+        std::fs::rename(from, to)
+    }
+
+    fn remove(&self, path: &Path) -> Result<(), std::io::Error> {
+        std::fs::remove_file(path)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata, std::io::Error> {
+        let meta = std::fs::metadata(path)?;
+        Ok(Metadata {
+            len: meta.len(),
+            modified: meta.modified()?,
+        })
+    }
+
+    fn writable(&self, path: &Path) -> bool {
+        std::fs::OpenOptions::new().append(true).open(path).is_ok()
+    }
+
+    fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+        let entries = std::fs::read_dir(dir)?;
+        Ok(entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .collect())
+    }
+}
+
+#[cfg(feature = "memory-fs")]
+pub use memory::MemoryFs;
+#[cfg(feature = "memory-fs")]
+use memory::{lock, Node};
+
+#[cfg(feature = "memory-fs")]
+mod memory {
+    use super::{File, Fs, Metadata};
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex, MutexGuard};
+
+    pub(crate) struct Inode {
+        pub(crate) data: Vec<u8>,
+        pub(crate) modified: std::time::SystemTime,
+    }
+
+    /// the content of a file, it stays with an opened file across renames
+    pub(crate) type Node = Arc<Mutex<Inode>>;
+
+    pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+        mutex.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// files in memory for `Log2::memory_fs()`, clones share the files, so
+    /// a test keeps one to look at what the rotation did, directories exist
+    /// implicitly, a relative path is relative to the directory `.`
+    #[derive(Clone, Default)]
+    pub struct MemoryFs {
+        files: Arc<Mutex<BTreeMap<PathBuf, Node>>>,
+    }
+
+    fn not_found(path: &Path) -> std::io::Error {
+        let message = format!("{} not found", path.display());
+        std::io::Error::new(std::io::ErrorKind::NotFound, message)
+    }
+
+    /// `./log.txt` and `log.txt` are the same file
+    fn normal(path: &Path) -> PathBuf {
+        path.strip_prefix(".").unwrap_or(path).into()
+    }
+
+    impl MemoryFs {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// the paths of all files, sorted
+        pub fn files(&self) -> Vec<PathBuf> {
+            lock(&self.files).keys().cloned().collect()
+        }
+
+        /// the content of a file
+        pub fn read(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+            let files = lock(&self.files);
+            let node = files.get(&normal(path.as_ref()))?;
+            let data = lock(node).data.clone();
+            Some(data)
+        }
+
+        /// put a file in place, e.g. one left by an earlier run
+        pub fn write(&self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) {
+            let inode = Inode {
+                data: content.as_ref().to_vec(),
+                modified: std::time::SystemTime::now(),
+            };
+            lock(&self.files).insert(normal(path.as_ref()), Arc::new(Mutex::new(inode)));
+        }
+    }
+
+    impl Fs for MemoryFs {
+        fn create(&self, path: &Path) -> Result<(), std::io::Error> {
+            self.open(path, None).map(drop)
+        }
+
+        fn open(&self, path: &Path, _: Option<u32>) -> Result<File, std::io::Error> {
+            let mut files = lock(&self.files);
+            let node = files.entry(normal(path)).or_insert_with(|| {
+                Arc::new(Mutex::new(Inode {
+                    data: Vec::new(),
+                    modified: std::time::SystemTime::now(),
+                }))
+            });
+            Ok(File::Memory(node.clone()))
+        }
+
+        fn rename(&self, from: &Path, to: &Path) -> Result<(), std::io::Error> {
+            let mut files = lock(&self.files);
+            let node = files.remove(&normal(from)).ok_or_else(|| not_found(from))?;
+            files.insert(normal(to), node);
+            Ok(())
+        }
+
+        fn remove(&self, path: &Path) -> Result<(), std::io::Error> {
+            let mut files = lock(&self.files);
+            files
+                .remove(&normal(path))
+                .map(drop)
+                .ok_or_else(|| not_found(path))
+        }
+
+        fn metadata(&self, path: &Path) -> Result<Metadata, std::io::Error> {
+            let files = lock(&self.files);
+            let node = files.get(&normal(path)).ok_or_else(|| not_found(path))?;
+            let inode = lock(node);
+            Ok(Metadata {
+                len: inode.data.len() as u64,
+                modified: inode.modified,
+            })
+        }
+
+        fn memory(&self) -> bool {
+            true
+        }
+
+        fn list(&self, dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+            let dir = normal(dir);
+            let files = lock(&self.files);
+            let within = |path: &&PathBuf| path.parent().map(normal).as_ref() == Some(&dir);
+            Ok(files.keys().filter(within).cloned().collect())
+        }
+    }
+}
@@ -10,6 +10,20 @@
 //!cargo add log2
 //!```
 //!
+//!For embedded devices, disable the default features to drop `chrono` and `colored`. Without
+//!`colored` stdout is plain, without `chrono` the timestamps are epoch millis, and without `gzip`
+//!or `lz4` the rotated files stay uncompressed, so a static musl binary pulls in only `log`.
+//!
+//!```
+//!cargo add log2 --no-default-features
+//!```
+//!
+//!The `minimal` feature stays as an alias of this build. It enables nothing by itself, so it has
+//!to come with `--no-default-features`, next to the default features it changes nothing.
+//!
+//!On `wasm32-unknown-unknown` the records are written without a worker thread, and the `console`
+//!feature prints them to the console of the browser, so crates using the log macros work in the web.
+//!
 //!## Log to stdout
 //!
 //!Simple to start.
@@ -52,6 +66,30 @@
 //!
 //!```
 //!
+//!Recolor the levels, brackets and time, e.g. for light terminals.
+//!
+//!```rust
+//!use log2::*;
+//!
+//!fn main() {
+//!let _log2 = log2::stdout()
+//!.theme(Theme {
+//!error: Color::Red.bold(),
+//!info: Color::Blue.normal(),
+//!..Default::default()
+//!})
+//!.start();
+//!
+//!info!("order was executed");
+//!error!("network connection was broken");
+//!}
+//!```
+//!
+//!Colors are on for a terminal only, `NO_COLOR`, `CLICOLOR=0` or a redirected stdout turn them off,
+//!`CLICOLOR_FORCE=1` or `FORCE_COLOR=1` keep them for CI logs which render ANSI.
+//!`.color(ColorChoice::Always)` or `.color(ColorChoice::Never)` overrides the detection, so does
+//!`LOG2_COLOR=always|never|auto`.
+//!
 //!## Log to file
 //!
 //!`log2` with default file size 100MB, max file count 10, you can change as you like. Note the `_log2` will
@@ -100,14 +138,154 @@
 //!log.8.txt
 //!log.9.txt
 //!```
+//!
+//!## Fatal errors
+//!
+//!`fatal!` logs at error level, waits until every queued record is written to the file, and exits the
+//!process. The exit code is 1 by default.
+//!
+//!```rust
+//!use log2::*;
+//!
+//!fn main() {
+//!let _log2 = log2::open("log.txt").fatal_code(2).start();
+//!
+//!fatal!("unable to load the config");
+//!}
+//!```
+//!
+//!## Capture in tests
+//!
+//!`capture()` keeps records in memory within the logging call, so tests can assert on them right away
+//!without temp files or waiting for the worker.
+//!
+//!```rust
+//!use log2::*;
+//!
+//!fn main() {
+//!let _log2 = log2::capture().start();
+//!
+//!info!("order executed");
+//!
+//!assert!(captured().contains("order executed"));
+//!assert!(captured().level(Level::Error).is_empty());
+//!}
+//!```
+//!
+//!## Configuration files
+//!
+//!With the `config` feature, `from_config()` builds the logger from a toml or yaml file, or from the
+//!config text itself.
+//!
+//!```toml
+//!path = "log/app.txt"
+//!size = 104857600
+//!rotate = 10
+//!level = "info"
+//!tee = false
+//!modules = ["app", "hyper"]
+//!deny_modules = ["hyper::proto::*"]
+//!format = "compact"
+//!```
+//!
+//!```rust
+//!use log2::*;
+//!
+//!fn main() {
+//!let _log2 = log2::from_config("log2.toml").unwrap().start();
+//!}
+//!```
+//!
+//!`start()` applies `LOG2_FILE`, `LOG2_LEVEL`, `LOG2_SIZE`, `LOG2_ROTATE`, `LOG2_TEE` and `LOG2_COLOR`
+//!from the environment on top of the builder values.
+#[cfg(feature = "chrono")]
 use chrono::Local;
-use colored::*;
 use core::fmt;
-use log::{Level, LevelFilter, Metadata, Record};
-use std::{io::Write, thread::JoinHandle};
+#[cfg(feature = "crossbeam")]
+use crossbeam_channel::{unbounded as channel, Receiver, Sender};
+use log::{LevelFilter, Metadata};
+#[cfg(not(feature = "crossbeam"))]
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::{
+    io::Write,
+    sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering},
+    thread::JoinHandle,
+};
+
+mod access;
+mod child;
+#[cfg(feature = "cli")]
+pub mod cli;
+mod compress;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(all(feature = "console", target_arch = "wasm32"))]
+mod console;
+#[cfg(feature = "encrypt")]
+mod crypt;
+#[cfg(feature = "disk")]
+mod disk;
+mod fs;
+#[cfg(feature = "mmap")]
+mod mapped;
+mod named;
+mod naming;
+mod policy;
+mod preset;
+mod quota;
+mod recorder;
+mod ring;
+mod rotating;
+#[cfg(feature = "hmac")]
+mod seal;
+#[cfg(unix)]
+mod streams;
+#[cfg(feature = "tokio")]
+mod task;
+mod theme;
+#[cfg(all(feature = "oslog", target_vendor = "apple"))]
+mod unified;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+mod uring;
 
+pub use access::{access, Access, AccessLog};
+pub use child::{pipe_child, ChildLogger};
+#[cfg(any(feature = "gzip", feature = "lz4"))]
+pub use compress::Codec;
+#[cfg(feature = "encrypt")]
+pub use crypt::decrypt;
+#[cfg(feature = "memory-fs")]
+pub use fs::MemoryFs;
 /// log macros
 pub use log::{debug, error, info, trace, warn};
+#[cfg(feature = "hmac")]
+pub use seal::verify;
+
+/// log record passed to enrichers
+pub use log::Record;
+
+#[cfg(feature = "config")]
+pub use config::{from_config, Log2Config};
+
+/// built-in layouts for `Log2::preset()`
+pub use preset::{Cef, Format};
+
+/// names of the rotated files for `Log2::naming()`
+pub use naming::Naming;
+pub use policy::{RotationPolicy, RotationState};
+
+/// the rotating file of the worker on its own
+pub use rotating::RotatingFile;
+
+/// loggers of subsystems with levels of their own or with bound fields, writing
+/// to the sinks of the running instance
+pub use named::{logger, with_fields, Logger};
+
+/// colors of the default stdout layout for `Log2::theme()`
+pub use theme::{Color, ColorChoice, Style, Theme};
+
+/// level of a single record
+pub use log::Level;
 
 /// log levels
 #[allow(non_camel_case_types)]
@@ -126,377 +304,3975 @@ fn get_level(level: String) -> LevelFilter {
     }
 }
 
-/// set the log level, the input can be both enum or name
-pub fn set_level<T: fmt::Display>(level: T) {
-    log::set_max_level(get_level(level.to_string()));
-}
+static FATAL_CODE: AtomicI32 = AtomicI32::new(1);
 
-enum Action {
-    Write(String),
-    Tee(String),
-    Flush,
-    Exit,
-    Redirect(String),
+/// the level set by the user, `log::max_level()` stays at trace while the
+/// flight recorder needs the records below it
+static LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Trace as usize);
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// the last record number, shared by every logger of the process
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+fn next_sequence() -> u64 {
+    SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1
 }
 
-/// handle for terminating log2
-pub struct Handle {
-    tx: std::sync::mpsc::Sender<Action>,
-    thread: Option<JoinHandle<()>>,
+/// the logging thread, looked up once per thread
+#[derive(Clone)]
+struct Thread {
+    id: u64,
+    name: std::sync::Arc<str>,
 }
 
-pub struct Log2 {
-    tx: std::sync::mpsc::Sender<Action>,
-    rx: Option<std::sync::mpsc::Receiver<Action>>,
-    levels: [ColoredString; 6],
-    path: String,
-    tee: bool,
-    module: bool,
-    filesize: u64,
-    count: usize,
-    level: String,
-    module_filter: Option<Box<dyn Fn(&str) -> bool + Send>>,
+impl Thread {
+    fn current() -> Thread {
+        thread_local! {
+            static CURRENT: Thread = {
+                let thread = std::thread::current();
+                // `ThreadId::as_u64` is unstable, the debug form is `ThreadId(n)`
+                let id = format!("{:?}", thread.id());
+                let id = id.trim_start_matches("ThreadId(").trim_end_matches(')');
+                Thread {
+                    id: id.parse().unwrap_or_default(),
+                    name: thread.name().unwrap_or("<unnamed>").into(),
+                }
+            };
+        }
+        CURRENT.with(|thread| thread.clone())
+    }
 }
 
-struct Context {
-    rx: std::sync::mpsc::Receiver<Action>,
-    path: String,
-    size: u64,
-    count: usize,
+#[cfg(unix)]
+extern "C" {
+    fn gethostname(name: *mut std::os::raw::c_char, len: usize) -> std::os::raw::c_int;
 }
 
-impl Log2 {
-    pub fn new() -> Self {
-        let (tx, rx) = std::sync::mpsc::channel();
-        let levels = [
-            "OFF".black(),
-            "ERROR".bright_red(),
-            "WARN".yellow(),
-            "INFO".green(),
-            "DEBUG".bright_blue(),
-            "TRACE".cyan(),
-        ];
-        Self {
-            tx,
-            rx: Some(rx),
-            levels,
-            path: String::new(),
-            tee: false,
-            module: true,
-            filesize: 100 * 1024 * 1024,
-            count: 10,
-            level: String::new(),
-            module_filter: None,
+/// name of this machine, empty if unknown
+fn hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        if unsafe { gethostname(buf.as_mut_ptr().cast(), buf.len()) } == 0 {
+            let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            return String::from_utf8_lossy(&buf[..len]).into_owned();
         }
     }
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default()
+}
 
-    pub fn module(mut self, show: bool) -> Log2 {
-        self.module = show;
-        self
-    }
+/// log at error level, flush all pending records and exit the process
+#[macro_export]
+macro_rules! fatal {
+    ($($arg:tt)+) => {{
+        $crate::error!($($arg)+);
+        $crate::fatal_exit()
+    }};
+}
 
-    // split the output to stdout
-    pub fn tee(mut self, stdout: bool) -> Log2 {
-        self.tee = stdout;
-        self
-    }
+#[doc(hidden)]
+pub fn fatal_exit() -> ! {
+    log::logger().flush();
+    std::process::exit(FATAL_CODE.load(Ordering::Relaxed))
+}
 
-    /// setup the maximum size for each file
-    pub fn size(mut self, filesize: u64) -> Log2 {
-        if self.count <= 1 {
-            self.filesize = std::u64::MAX;
-        } else {
-            self.filesize = filesize;
+/// log the elapsed time at the given level (default info) when the returned guard drops
+#[macro_export]
+macro_rules! timer {
+    ($level:expr, $name:expr) => {
+        $crate::Timer::new($name, $level, module_path!(), file!(), line!())
+    };
+    ($name:expr) => {
+        $crate::timer!($crate::level::Info, $name)
+    };
+}
+
+/// guard created by `timer!`
+pub struct Timer {
+    name: String,
+    level: LevelFilter,
+    module: &'static str,
+    file: &'static str,
+    line: u32,
+    start: std::time::Instant,
+}
+
+impl Timer {
+    #[doc(hidden)]
+    pub fn new(
+        name: impl Into<String>,
+        level: LevelFilter,
+        module: &'static str,
+        file: &'static str,
+        line: u32,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            level,
+            module,
+            file,
+            line,
+            start: std::time::Instant::now(),
         }
-        self
     }
+}
 
-    /// setup the rotate count
-    pub fn rotate(mut self, count: usize) -> Log2 {
-        self.count = count;
-        if self.count <= 1 {
-            self.filesize = std::u64::MAX;
-        }
-        self
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let level = match self.level.to_level() {
+            Some(level) if level <= log::max_level() => level,
+            _ => return,
+        };
+        let elapsed = self.start.elapsed();
+        log::logger().log(
+            &Record::builder()
+                .level(level)
+                .target(self.module)
+                .module_path_static(Some(self.module))
+                .file_static(Some(self.file))
+                .line(Some(self.line))
+                .args(format_args!("{} took {:?}", self.name, elapsed))
+                .build(),
+        );
     }
+}
 
-    /// provide a way to filter by module
-    pub fn module_filter(mut self, filter: impl Fn(&str) -> bool + Send + 'static) -> Log2 {
-        self.module_filter = Some(Box::new(filter));
-        self
+/// flush queued records on normal process exit, even if the `Handle` is leaked or
+/// held in a static, this is installed by `start()` already
+pub fn install_exit_flush() {
+    // a page doesn't exit, and wasm writes every record at once
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| unsafe {
+            atexit(exit_flush);
+        });
     }
+}
 
-    pub fn level<T: fmt::Display>(mut self, name: T) -> Self {
-        self.level = name.to_string();
-        self
+#[cfg(not(target_arch = "wasm32"))]
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> std::os::raw::c_int;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+extern "C" fn exit_flush() {
+    log::logger().flush();
+}
+
+/// write out the queued records and stop the worker of the latest `start()`,
+/// also when its `Handle` is held in a static, later records are dropped,
+/// false if the records weren't all written within the timeout
+pub fn shutdown(timeout: std::time::Duration) -> bool {
+    let logger = ACTIVE.write().ok().and_then(|mut active| active.take());
+    let Some(logger) = logger else {
+        return true;
+    };
+    // the file of `sync(true)` is flushed on every write
+    if logger.direct.is_some() {
+        return true;
+    }
+    let (done, wait) = std::sync::mpsc::channel();
+    let done = Box::new(move || {
+        let _ = done.send(());
+    });
+    if !logger.tx.send(Action::Flush(done)) {
+        return false;
     }
+    logger.tx.send(Action::Exit);
+    let waited = logger.tx.wait(|| wait.recv_timeout(timeout));
+    waited.is_some_and(|result| result.is_ok())
+}
 
-    /// start the log2 instance
-    pub fn start(self) -> Handle {
-        let n = self.level.clone();
-        let handle = start_log2(self);
-        if !n.is_empty() {
-            set_level(n);
-        }
-        handle
+/// the level of `set_level()` or the builder
+fn current_level() -> LevelFilter {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
     }
 }
 
-unsafe impl Sync for Log2 {}
+/// the level of a named logger, or the global one
+fn level_of(target: &str) -> LevelFilter {
+    named::level(target).unwrap_or_else(current_level)
+}
 
-impl log::Log for Log2 {
-    fn enabled(&self, metadata: &Metadata) -> bool {
-        // this seems no effect at all
-        metadata.level() >= Level::Error
+/// set the log level, the input can be both enum or name
+pub fn set_level<T: fmt::Display>(level: T) {
+    let level = get_level(level.to_string());
+    LEVEL.store(level as usize, Ordering::Relaxed);
+    widen();
+}
+
+/// let `log` pass what the global level or a named logger wants, the flight
+/// recorder wants everything
+fn widen() {
+    if !RECORDING.load(Ordering::Relaxed) {
+        log::set_max_level(current_level().max(named::widest()));
     }
+}
 
-    fn log(&self, record: &Record) {
-        let module = record.module_path().unwrap_or("unknown");
+/// target of log2's own diagnostics, e.g. a failed rotation
+pub const INTERNAL: &str = "log2::internal";
 
-        // module filter
-        if let Some(filter) = &self.module_filter {
-            if !filter(module) {
-                return;
-            }
-        }
+/// owned copy of a log record, formatted by the worker, one entry feeds both
+/// stdout and the file so they always see records in the same order
+struct Entry {
+    sequence: u64,
+    thread: Option<Thread>,
+    level: Level,
+    module: std::borrow::Cow<'static, str>,
+    time: std::time::SystemTime,
+    message: String,
+    /// a line of `Handle::write_line()`, written as it is
+    raw: bool,
+}
 
-        // module
-        let mut origin = String::new();
-        if self.module {
-            origin = format!("[{}] ", module);
-        }
+/// recycled string buffers shared by the callers and the worker
+#[derive(Default)]
+struct Pool {
+    free: std::sync::Mutex<Vec<String>>,
+}
 
-        // stdout
-        if self.tee {
-            let level = &self.levels[record.level() as usize];
-            let open = "[".truecolor(0x87, 0x87, 0x87);
-            let close = "]".truecolor(0x87, 0x87, 0x87);
-            let line = format!(
-                "{open}{}{close} {open}{}{close} {origin}{}",
-                Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
-                level,
-                record.args()
-            );
-            let _ = self.tx.send(Action::Tee(line));
-        }
+impl Pool {
+    /// buffers kept for reuse, larger ones are dropped
+    const SIZE: usize = 1024;
+    const CAPACITY: usize = 64 * 1024;
 
-        // file
-        if self.path.len() > 0 {
-            let line = format!(
-                "[{}] [{}] {origin}{}\n",
-                Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
-                record.level(),
-                record.args()
-            );
-            let _ = self.tx.send(Action::Write(line));
+    fn take(&self) -> String {
+        // never wait for the lock on the hot path, allocate instead
+        match self.free.try_lock() {
+            Ok(mut free) => free.pop().unwrap_or_default(),
+            Err(_) => String::new(),
         }
     }
 
-    fn flush(&self) {
-        let _ = self.tx.send(Action::Flush);
+    fn give(&self, mut buf: String) {
+        if buf.capacity() > Self::CAPACITY {
+            return;
+        }
+        buf.clear();
+        if let Ok(mut free) = self.free.lock() {
+            if free.len() < Self::SIZE {
+                free.push(buf);
+            }
+        }
     }
 }
 
-impl Handle {
-    pub fn stop(&mut self) {
-        if let Some(thread) = self.thread.take() {
-            let _ = self.tx.send(Action::Exit);
-            let _ = thread.join();
-        }
+/// a record kept by `capture()`
+#[derive(Debug, Clone)]
+pub struct Captured {
+    pub level: Level,
+    pub module: String,
+    pub message: String,
+}
+
+/// snapshot of the captured records, see `captured()`
+#[derive(Debug, Clone, Default)]
+pub struct Captures(pub Vec<Captured>);
+
+impl Captures {
+    /// any message contains the text
+    pub fn contains(&self, text: &str) -> bool {
+        self.0.iter().any(|r| r.message.contains(text))
     }
 
-    pub fn set_level<T: fmt::Display>(&self, level: T) {
-        crate::set_level(level);
+    /// only the records at the level
+    pub fn level(&self, level: Level) -> Captures {
+        Captures(
+            self.0
+                .iter()
+                .filter(|r| r.level == level)
+                .cloned()
+                .collect(),
+        )
     }
 
-    /// redirect the output file
-    pub fn redirect(&mut self, path: &str) {
-        // create directory
-        let dir = std::path::Path::new(path);
-        if let Some(dir) = dir.parent() {
-            let _ = std::fs::create_dir_all(&dir);
-        }
+    /// only the records of the module and its submodules
+    pub fn module(&self, module: &str) -> Captures {
+        let records = self.0.iter().filter(|r| within(&r.module, module));
+        Captures(records.cloned().collect())
+    }
 
-        // check file, panic if error
-        std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
-            .expect("error to open file");
+    pub fn messages(&self) -> Vec<&str> {
+        self.0.iter().map(|r| r.message.as_str()).collect()
+    }
 
-        // redirect log file
-        let _ = self.tx.send(Action::Redirect(path.into()));
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 }
 
-impl Drop for Handle {
-    fn drop(&mut self) {
-        self.stop();
+static CAPTURED: std::sync::Mutex<Vec<Captured>> = std::sync::Mutex::new(Vec::new());
+
+/// the records captured so far, kept in the logging call so nothing waits for the worker
+pub fn captured() -> Captures {
+    match CAPTURED.lock() {
+        Ok(records) => Captures(records.clone()),
+        Err(_) => Captures::default(),
     }
 }
 
-fn rotate(ctx: &Context) -> Result<std::fs::File, std::io::Error> {
-    let size = std::fs::metadata(&ctx.path)?.len();
-    let dot = ctx.path.rfind(".").unwrap_or(0);
-    let mut suffix = "";
-    let mut prefix = &ctx.path[..];
-    if dot > 0 {
-        suffix = &ctx.path[dot..];
-        prefix = &ctx.path[0..dot];
+/// forget the records captured so far
+pub fn clear_captured() {
+    if let Ok(mut records) = CAPTURED.lock() {
+        records.clear();
     }
+}
 
-    if size >= ctx.size {
-        for i in (0..ctx.count - 1).rev() {
-            let mut a = format!("{prefix}.{}{suffix}", i);
-            if i == 0 {
-                a = ctx.path.clone();
-            }
-            let b = format!("{prefix}.{}{suffix}", i + 1);
-            let _ = std::fs::rename(&a, &b);
+/// window of the last formatted lines for `Handle::tail()`
+struct Recent {
+    capacity: usize,
+    lines: std::sync::Mutex<std::collections::VecDeque<String>>,
+}
+
+impl Recent {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: std::sync::Mutex::new(std::collections::VecDeque::with_capacity(capacity)),
         }
     }
 
-    let file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&ctx.path)?;
+    fn push(&self, line: &str) {
+        let Ok(mut lines) = self.lines.lock() else {
+            return;
+        };
+        // reuse the oldest buffer once the window is full
+        let mut buf = match lines.len() >= self.capacity {
+            true => lines.pop_front().unwrap_or_default(),
+            false => String::new(),
+        };
+        buf.clear();
+        buf.push_str(line.trim_end_matches('\n'));
+        lines.push_back(buf);
+    }
 
-    Ok(file)
+    fn tail(&self, n: usize) -> Vec<String> {
+        let Ok(lines) = self.lines.lock() else {
+            return Vec::new();
+        };
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(n))
+            .cloned()
+            .collect()
+    }
 }
 
-fn now() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
+/// counters shared by the callers, the worker and the handle
+#[derive(Default)]
+struct Counters {
+    records: [AtomicU64; 6],
+    bytes: AtomicU64,
+    rotations: AtomicU64,
+    queued: AtomicU64,
+    dropped: AtomicU64,
 }
 
-fn worker(mut ctx: Context) -> Result<(), std::io::Error> {
-    let mut target: Option<std::fs::File> = None;
-    let mut size: u64 = 0;
-    let mut last = size;
+/// bumps the counters, mirrored to the `metrics` recorder when enabled
+impl Counters {
+    fn record(&self, level: Level) {
+        self.records[level as usize].fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("log2_records_total", "level" => level.as_str()).increment(1);
+    }
 
-    if ctx.path.len() > 0 {
-        let file = rotate(&ctx)?;
-        size = file.metadata()?.len();
-        target = Some(file);
+    fn queued(&self) {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::gauge!("log2_queue_depth").increment(1.0);
     }
 
-    let timeout = std::time::Duration::from_secs(1);
-    let mut ts = now();
+    fn handled(&self) {
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::gauge!("log2_queue_depth").decrement(1.0);
+    }
 
-    loop {
-        if let Ok(action) = ctx.rx.recv_timeout(timeout) {
-            match action {
-                Action::Write(line) => {
-                    let file = target.as_mut().unwrap();
-                    let buf = line.as_bytes();
-                    file.write_all(buf)?;
-                    size += buf.len() as u64;
-                    if size >= ctx.size {
-                        let f = rotate(&ctx)?;
-                        size = f.metadata()?.len();
-                        target = Some(f);
-                    }
-                }
-                Action::Tee(line) => {
-                    println!("{line}");
-                }
-                Action::Flush => {
-                    if target.is_some() {
-                        let file = target.as_mut().unwrap();
-                        file.flush()?;
-                    }
-                }
-                Action::Exit => {
-                    if target.is_some() {
-                        let file = target.as_mut().unwrap();
-                        file.flush()?;
-                    }
-                    break;
-                }
-                Action::Redirect(path) => {
-                    ctx.path = path;
-                    let file = rotate(&ctx)?;
-                    size = file.metadata()?.len();
-                    target = Some(file);
-                }
-            }
-        }
-        // flush every 1s
-        if size > last && target.is_some() {
-            let n = now();
-            if n - ts >= 1 {
-                ts = n;
-                let file = target.as_mut().unwrap();
-                file.flush()?;
-                last = size;
-            }
-        }
+    fn dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("log2_dropped_total").increment(1);
     }
 
-    Ok(())
+    fn written(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("log2_bytes_total").increment(bytes);
+    }
+
+    fn rotated(&self) {
+        self.rotations.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        metrics::counter!("log2_rotations_total").increment(1);
+    }
+
+    fn failed(&self) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!("log2_write_errors_total").increment(1);
+    }
 }
 
-/// start the log2 instance by default
-pub fn start() -> Handle {
-    let mut logger = Log2::new();
-    logger.tee = true;
-    start_log2(logger)
+/// runtime metrics since start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub error: u64,
+    pub warn: u64,
+    pub info: u64,
+    pub debug: u64,
+    pub trace: u64,
+    /// bytes written to the log files
+    pub bytes: u64,
+    /// rotations performed
+    pub rotations: u64,
+    /// records waiting for the worker
+    pub queued: u64,
+    /// records lost because the queue was full or gone
+    pub dropped: u64,
 }
 
-/// create a log2 instance to stdout
-pub fn stdout() -> Log2 {
-    let mut logger = Log2::new();
-    logger.tee = true;
-    logger
+enum Action {
+    Record(Entry),
+    /// called once everything queued before it is flushed
+    Flush(Box<dyn FnOnce() + Send>),
+    Exit,
+    Redirect(std::path::PathBuf),
+    Tee(bool),
+    Format(std::sync::Arc<dyn Formatter>),
+    /// file size and count
+    Rotation(u64, usize),
+    /// below the level, kept by the flight recorder
+    Remember(Entry),
+    /// write out what the flight recorder kept
+    Dump,
 }
 
-/// log to file
-pub fn open(path: &str) -> Log2 {
-    // create directory
-    let dir = std::path::Path::new(path);
-    if let Some(dir) = dir.parent() {
-        let _ = std::fs::create_dir_all(&dir);
-    }
+/// computed fields attached to a record, rendered as `key=value` after the message
+pub type Fields = Vec<(String, String)>;
 
-    // check file, panic if error
-    std::fs::OpenOptions::new()
+/// enrich a record with computed fields before it is formatted
+pub trait Enricher: Send + Sync {
+    fn enrich(&self, record: &Record) -> Fields;
+}
+
+impl<F> Enricher for F
+where
+    F: Fn(&Record) -> Fields + Send + Sync,
+{
+    fn enrich(&self, record: &Record) -> Fields {
+        self(record)
+    }
+}
+
+/// a record on its way to the worker, open to changes by the layers
+#[derive(Debug, Clone)]
+pub struct RecordView {
+    /// a changed level doesn't pass the level filter again
+    pub level: Level,
+    pub module: std::borrow::Cow<'static, str>,
+    /// the formatted message with the fields of the enrichers
+    pub message: String,
+}
+
+/// a step of the chain run on every record before formatting, `None` drops the record
+pub trait Layer: Send + Sync {
+    fn layer(&self, view: RecordView) -> Option<RecordView>;
+}
+
+impl<F> Layer for F
+where
+    F: Fn(RecordView) -> Option<RecordView> + Send + Sync,
+{
+    fn layer(&self, view: RecordView) -> Option<RecordView> {
+        self(view)
+    }
+}
+
+/// the pieces of a record handed to a formatter
+pub struct Parts<'a> {
+    /// process-wide record number, zero unless `Log2::sequence()` is on
+    pub sequence: u64,
+    /// empty unless `Log2::hostname()` is on
+    pub hostname: &'a str,
+    /// zero unless `Log2::pid()` is on
+    pub pid: u32,
+    /// zero and empty unless `Log2::thread()` is on
+    pub thread_id: u64,
+    pub thread_name: &'a str,
+    pub time: &'a str,
+    /// since the logger started
+    pub elapsed: std::time::Duration,
+    /// since the previous record
+    pub delta: std::time::Duration,
+    pub level: Level,
+    /// the name of the level, renamed by `Log2::label()`
+    pub label: &'a str,
+    pub module: &'a str,
+    pub message: &'a str,
+}
+
+/// lay out a record as one line, without the trailing newline, for stdout and the file
+pub trait Formatter: Send + Sync {
+    fn format(&self, line: &mut String, parts: &Parts);
+}
+
+impl<F> Formatter for F
+where
+    F: Fn(&mut String, &Parts) + Send + Sync,
+{
+    fn format(&self, line: &mut String, parts: &Parts) {
+        self(line, parts)
+    }
+}
+
+/// the lines after the first one of a multi-line message, e.g. a backtrace
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Continuation {
+    /// as they are, starting at the first column
+    #[default]
+    Off,
+    /// indented under the first line of the message
+    Align,
+    /// prefixed, e.g. with `| `
+    Prefix(String),
+}
+
+/// the end of the lines in the file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    /// `\r\n`, for Notepad and other Windows tools
+    CrLf,
+}
+
+/// digits of the second fractions in the timestamps of the default layout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimePrecision {
+    #[default]
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl TimePrecision {
+    /// nanoseconds per unit
+    fn unit(self) -> u128 {
+        match self {
+            TimePrecision::Millis => 1_000_000,
+            TimePrecision::Micros => 1_000,
+            TimePrecision::Nanos => 1,
+        }
+    }
+}
+
+/// when the worker calls `sync_data` to make written records durable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// leave it to the operating system
+    Never,
+    /// sync at most once per second
+    EverySecond,
+    /// sync after every record
+    EveryWrite,
+}
+
+/// what becomes of an old archive, see `Log2::on_prune()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// remove it as usual
+    Delete,
+    /// leave it on disk, renamed with a timestamp so no rotation reaches it
+    Keep,
+}
+
+type ModuleFilter = Box<dyn Fn(&str) -> bool + Send>;
+type RecordFilter = Box<dyn Fn(&Record) -> bool + Send>;
+
+/// the first line of every opened file, see `Log2::header()`
+type Header = std::sync::Arc<dyn Fn() -> String + Send + Sync>;
+type Footer = std::sync::Arc<dyn Fn(&Trailer) -> String + Send + Sync>;
+type OnRotate = std::sync::Arc<dyn Fn(&std::path::Path) + Send + Sync>;
+type OnPrune = std::sync::Arc<dyn Fn(&std::path::Path) -> Decision + Send + Sync>;
+#[cfg(feature = "disk")]
+type OnLowDisk = std::sync::Arc<dyn Fn(u64) + Send + Sync>;
+
+/// what a file held when it was rotated away, see `Log2::footer()`
+#[derive(Debug, Clone, Copy)]
+pub struct Trailer {
+    pub time: std::time::SystemTime,
+    /// the size of the file before the footer
+    pub bytes: u64,
+    /// records written into the file since it was opened
+    pub records: u64,
+}
+
+/// modules allowed by the config, all when empty, replaced on reload
+type Modules = std::sync::Arc<std::sync::RwLock<Vec<String>>>;
+
+/// where the worker writes the file
+enum Sink {
+    File(std::fs::File, Retry),
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    Uring(Box<uring::Uring>),
+    #[cfg(feature = "mmap")]
+    Mapped(Box<mapped::Mapped>),
+    #[cfg(feature = "memory-fs")]
+    Memory(fs::File),
+}
+
+impl Sink {
+    fn sync_data(&mut self) -> Result<(), std::io::Error> {
+        match self {
+            Sink::File(file, retry) => retry.run(|| file.sync_data()),
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            Sink::Uring(uring) => uring.sync_data(),
+            #[cfg(feature = "mmap")]
+            Sink::Mapped(mapped) => mapped.sync_data(),
+            #[cfg(feature = "memory-fs")]
+            Sink::Memory(_) => Ok(()),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
+        match self {
+            Sink::File(file, retry) => retry.run(|| file.write(buf)),
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            Sink::Uring(uring) => uring.write(buf),
+            #[cfg(feature = "mmap")]
+            Sink::Mapped(mapped) => mapped.write(buf),
+            #[cfg(feature = "memory-fs")]
+            Sink::Memory(memory) => memory.write(buf),
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize, std::io::Error> {
+        match self {
+            Sink::File(file, retry) => retry.run(|| file.write_vectored(bufs)),
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            Sink::Uring(uring) => uring.write_vectored(bufs),
+            #[cfg(feature = "mmap")]
+            Sink::Mapped(mapped) => mapped.write_vectored(bufs),
+            #[cfg(feature = "memory-fs")]
+            Sink::Memory(memory) => memory.write_vectored(bufs),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        match self {
+            Sink::File(file, retry) => retry.run(|| file.flush()),
+            #[cfg(all(feature = "io-uring", target_os = "linux"))]
+            Sink::Uring(uring) => uring.flush(),
+            #[cfg(feature = "mmap")]
+            Sink::Mapped(mapped) => mapped.flush(),
+            #[cfg(feature = "memory-fs")]
+            Sink::Memory(memory) => memory.flush(),
+        }
+    }
+}
+
+type Target = std::io::BufWriter<Sink>;
+
+/// the retries of `Log2::retry()`
+#[derive(Debug, Clone, Copy, Default)]
+struct Retry {
+    attempts: u32,
+    backoff: std::time::Duration,
+}
+
+impl Retry {
+    /// run again after a transient error, waiting twice as long each time
+    fn run<T>(self, mut f: impl FnMut() -> Result<T, std::io::Error>) -> Result<T, std::io::Error> {
+        let mut backoff = self.backoff;
+        for _ in 0..self.attempts {
+            match f() {
+                Err(e) if transient(&e) => {
+                    std::thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+        f()
+    }
+}
+
+/// an error that might be gone a moment later, e.g. a hiccup of a network
+/// filesystem
+fn transient(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind::*;
+    matches!(
+        e.kind(),
+        Interrupted | WouldBlock | TimedOut | ResourceBusy | StaleNetworkFileHandle
+    )
+}
+
+/// sending side of the queue, to a worker thread or a tokio task
+#[derive(Clone)]
+enum Tx {
+    Thread(Sender<Action>),
+    #[cfg(feature = "tokio")]
+    Task(tokio::sync::mpsc::UnboundedSender<Action>),
+}
+
+impl Tx {
+    fn send(&self, action: Action) -> bool {
+        match self {
+            Tx::Thread(tx) => tx.send(action).is_ok(),
+            #[cfg(feature = "tokio")]
+            Tx::Task(tx) => tx.send(action).is_ok(),
+        }
+    }
+
+    /// wait for the worker, inside a multi-thread runtime the other tasks, the
+    /// worker too, move off this thread meanwhile, on the only thread of a
+    /// current-thread runtime the task would never run, none then
+    fn wait<T>(&self, wait: impl FnOnce() -> T) -> Option<T> {
+        match self {
+            Tx::Thread(_) => Some(wait()),
+            #[cfg(feature = "tokio")]
+            Tx::Task(_) => match tokio::runtime::Handle::try_current() {
+                Err(_) => Some(wait()),
+                Ok(runtime) => match runtime.runtime_flavor() {
+                    tokio::runtime::RuntimeFlavor::CurrentThread => None,
+                    _ => Some(tokio::task::block_in_place(wait)),
+                },
+            },
+        }
+    }
+}
+
+/// handle for terminating log2
+pub struct Handle {
+    tx: Tx,
+    thread: Option<JoinHandle<()>>,
+    #[cfg(feature = "tokio")]
+    task: Option<tokio::task::JoinHandle<()>>,
+    path: std::path::PathBuf,
+    count: usize,
+    naming: Naming,
+    rotation_name: Option<std::sync::Arc<naming::RotationName>>,
+    fs: std::sync::Arc<dyn fs::Fs>,
+    template: Option<String>,
+    counters: std::sync::Arc<Counters>,
+    recent: Option<std::sync::Arc<Recent>>,
+    direct: Option<std::sync::Arc<std::sync::Mutex<Direct>>>,
+    #[cfg(feature = "watch")]
+    modules: Option<Modules>,
+    #[cfg(feature = "watch")]
+    watcher: Option<notify::RecommendedWatcher>,
+}
+
+/// a cloneable part of the `Handle` for other components to flush and change
+/// the settings, it doesn't keep the worker running, see `Handle::downgrade()`
+#[derive(Clone)]
+pub struct Controller {
+    tx: Tx,
+    counters: std::sync::Arc<Counters>,
+    recent: Option<std::sync::Arc<Recent>>,
+    direct: Option<std::sync::Arc<std::sync::Mutex<Direct>>>,
+}
+
+/// a log file on disk, either the active file or a rotated one
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub path: std::path::PathBuf,
+    pub size: u64,
+    pub modified: std::time::SystemTime,
+}
+
+pub struct Log2 {
+    tx: Tx,
+    rx: Option<Receiver<Action>>,
+    path: std::path::PathBuf,
+    fallback: Option<std::path::PathBuf>,
+    tee: bool,
+    module: bool,
+    filesize: u64,
+    count: usize,
+    naming: Naming,
+    rotation_name: Option<std::sync::Arc<naming::RotationName>>,
+    fs: std::sync::Arc<dyn fs::Fs>,
+    policy: Option<std::sync::Arc<dyn RotationPolicy>>,
+    template: Option<String>,
+    rotate_on_open: bool,
+    shared: bool,
+    latest: Option<std::path::PathBuf>,
+    compress: bool,
+    #[cfg(any(feature = "gzip", feature = "lz4"))]
+    codec: Codec,
+    #[cfg(feature = "bundle")]
+    bundle: Option<u32>,
+    #[cfg(feature = "encrypt")]
+    encrypt: Option<[u8; 32]>,
+    #[cfg(feature = "hmac")]
+    hmac: Option<Vec<u8>>,
+    #[cfg(feature = "disk")]
+    low_disk: Option<u64>,
+    #[cfg(feature = "disk")]
+    on_low_disk: Option<OnLowDisk>,
+    on_rotate: Option<OnRotate>,
+    on_prune: Option<OnPrune>,
+    flush_interval: std::time::Duration,
+    sync_policy: SyncPolicy,
+    retry: Retry,
+    buffer: usize,
+    sharded: bool,
+    #[cfg(feature = "io-uring")]
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    io_uring: bool,
+    #[cfg(feature = "mmap")]
+    mmap: bool,
+    level: Option<LevelFilter>,
+    tee_level: Option<LevelFilter>,
+    file_level: Option<LevelFilter>,
+    daily_quota: Option<u64>,
+    fatal_code: i32,
+    capture_panics: bool,
+    #[cfg(unix)]
+    std_streams: bool,
+    module_filter: Option<ModuleFilter>,
+    filter: Option<RecordFilter>,
+    allow: Vec<String>,
+    deny: Vec<String>,
+    modules: Option<Modules>,
+    enrichers: Vec<Box<dyn Enricher>>,
+    layers: Vec<Box<dyn Layer>>,
+    pool: std::sync::Arc<Pool>,
+    ring: Option<std::sync::Arc<ring::Ring>>,
+    recorder: usize,
+    recent: Option<std::sync::Arc<Recent>>,
+    capture: bool,
+    #[cfg(all(feature = "oslog", target_vendor = "apple"))]
+    os_log: bool,
+    tee_format: Option<std::sync::Arc<dyn Formatter>>,
+    file_format: Option<std::sync::Arc<dyn Formatter>>,
+    theme: Theme,
+    color: ColorChoice,
+    labels: [String; 6],
+    align: bool,
+    continuation: Continuation,
+    escape: bool,
+    tee_escape: bool,
+    strip_ansi: bool,
+    line_ending: LineEnding,
+    header: Option<Header>,
+    footer: Option<Footer>,
+    #[cfg(unix)]
+    mode: Option<u32>,
+    #[cfg(unix)]
+    owner: Option<(u32, u32)>,
+    scrub: Scrub,
+    #[cfg(feature = "timezone")]
+    timezone: Option<chrono_tz::Tz>,
+    precision: TimePrecision,
+    sequence: bool,
+    hostname: bool,
+    pid: bool,
+    thread: bool,
+    elapsed: bool,
+    delta: bool,
+    sync: bool,
+    direct: Option<std::sync::Arc<std::sync::Mutex<Direct>>>,
+    counters: std::sync::Arc<Counters>,
+}
+
+struct Context {
+    path: std::path::PathBuf,
+    size: u64,
+    count: usize,
+    naming: Naming,
+    rotation_name: Option<std::sync::Arc<naming::RotationName>>,
+    fs: std::sync::Arc<dyn fs::Fs>,
+    policy: Option<std::sync::Arc<dyn RotationPolicy>>,
+    template: Option<String>,
+    /// rotate the file of the previous run once, or the file that
+    /// `Log2::rotation_policy()` asked for
+    fresh: AtomicBool,
+    /// appended to by other processes too, see `Log2::shared()`
+    shared: bool,
+    /// device and inode of the active file, to notice a rotation by another process
+    #[cfg(unix)]
+    inode: (u64, u64),
+    /// the symlink of `Log2::link_latest()`
+    latest: Option<std::path::PathBuf>,
+    compressor: Option<compress::Compressor>,
+    on_prune: Option<OnPrune>,
+    /// the free space of `Log2::low_disk()`
+    #[cfg(feature = "disk")]
+    disk: Option<disk::Disk>,
+    /// the macs of `Log2::hmac()`
+    #[cfg(feature = "hmac")]
+    chain: Option<seal::Chain>,
+    interval: std::time::Duration,
+    sync: SyncPolicy,
+    retry: Retry,
+    buffer: usize,
+    sharded: bool,
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    io_uring: bool,
+    #[cfg(feature = "mmap")]
+    mmap: bool,
+    tee: bool,
+    tee_level: LevelFilter,
+    file_level: LevelFilter,
+    quota: Option<quota::Quota>,
+    module: bool,
+    levels: [String; 6],
+    labels: [String; 6],
+    pads: [usize; 6],
+    continuation: Continuation,
+    escape: bool,
+    tee_escape: bool,
+    strip_ansi: bool,
+    line_ending: LineEnding,
+    header: Option<Header>,
+    footer: Option<Footer>,
+    /// records written into the active file
+    records: AtomicU64,
+    /// when the active file was opened
+    opened: std::time::SystemTime,
+    #[cfg(unix)]
+    mode: Option<u32>,
+    #[cfg(unix)]
+    owner: Option<(u32, u32)>,
+    brackets: (String, String),
+    time: Style,
+    pool: std::sync::Arc<Pool>,
+    ring: Option<std::sync::Arc<ring::Ring>>,
+    recorder: usize,
+    recent: Option<std::sync::Arc<Recent>>,
+    tee_format: Option<std::sync::Arc<dyn Formatter>>,
+    file_format: Option<std::sync::Arc<dyn Formatter>>,
+    /// for the records of the ring, the callers skip them
+    layers: Vec<Box<dyn Layer>>,
+    scrub: Scrub,
+    #[cfg(feature = "timezone")]
+    timezone: Option<chrono_tz::Tz>,
+    precision: TimePrecision,
+    sequence: bool,
+    hostname: Option<String>,
+    pid: Option<u32>,
+    elapsed: bool,
+    delta: bool,
+    start: std::time::SystemTime,
+    counters: std::sync::Arc<Counters>,
+    tx: Tx,
+}
+
+impl Context {
+    /// queue a diagnostic about log2 itself behind the pending records
+    fn report(&self, level: Level, message: String) {
+        report(&self.tx, &self.counters, level, message);
+    }
+}
+
+fn report(tx: &Tx, counters: &Counters, level: Level, message: String) {
+    let entry = Entry {
+        sequence: next_sequence(),
+        thread: None,
+        level,
+        module: std::borrow::Cow::Borrowed(INTERNAL),
+        time: now(),
+        message,
+        raw: false,
+    };
+    counters.record(level);
+    counters.queued();
+    if !tx.send(Action::Record(entry)) {
+        counters.handled();
+        counters.dropped();
+    }
+}
+
+/// the module is the parent or one of its submodules
+fn within(module: &str, parent: &str) -> bool {
+    module == parent
+        || module
+            .strip_prefix(parent)
+            .is_some_and(|m| m.starts_with("::"))
+}
+
+/// a module against a glob like `h2::*`, or a plain name with its submodules
+fn matches(module: &str, pattern: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return within(module, pattern);
+    }
+    let (text, pattern): (Vec<char>, Vec<char>) =
+        (module.chars().collect(), pattern.chars().collect());
+    let (mut t, mut p) = (0, 0);
+    // the last star and the text position it was tried at
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((s, m)) = star {
+            p = s + 1;
+            t = m + 1;
+            star = Some((s, m + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl Log2 {
+    pub fn new() -> Self {
+        let (tx, rx) = channel();
+        Self {
+            tx: Tx::Thread(tx),
+            rx: Some(rx),
+            path: std::path::PathBuf::new(),
+            fallback: None,
+            tee: false,
+            module: true,
+            filesize: 100 * 1024 * 1024,
+            count: 10,
+            naming: Naming::Index,
+            rotation_name: None,
+            fs: std::sync::Arc::new(fs::Disk),
+            policy: None,
+            template: None,
+            rotate_on_open: false,
+            shared: false,
+            latest: None,
+            compress: false,
+            #[cfg(any(feature = "gzip", feature = "lz4"))]
+            codec: Codec::default(),
+            #[cfg(feature = "bundle")]
+            bundle: None,
+            #[cfg(feature = "encrypt")]
+            encrypt: None,
+            #[cfg(feature = "hmac")]
+            hmac: None,
+            #[cfg(feature = "disk")]
+            low_disk: None,
+            #[cfg(feature = "disk")]
+            on_low_disk: None,
+            on_rotate: None,
+            on_prune: None,
+            flush_interval: std::time::Duration::from_secs(1),
+            sync_policy: SyncPolicy::Never,
+            retry: Retry::default(),
+            buffer: 64 * 1024,
+            sharded: false,
+            #[cfg(feature = "io-uring")]
+            io_uring: false,
+            #[cfg(feature = "mmap")]
+            mmap: false,
+            level: None,
+            tee_level: None,
+            file_level: None,
+            daily_quota: None,
+            fatal_code: 1,
+            capture_panics: false,
+            #[cfg(unix)]
+            std_streams: false,
+            module_filter: None,
+            filter: None,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            modules: None,
+            enrichers: Vec::new(),
+            layers: Vec::new(),
+            pool: Default::default(),
+            ring: None,
+            recorder: 0,
+            recent: None,
+            capture: false,
+            #[cfg(all(feature = "oslog", target_vendor = "apple"))]
+            os_log: false,
+            tee_format: None,
+            file_format: None,
+            theme: Theme::default(),
+            color: ColorChoice::Auto,
+            labels: ["OFF", "ERROR", "WARN", "INFO", "DEBUG", "TRACE"].map(String::from),
+            align: false,
+            continuation: Continuation::Off,
+            escape: true,
+            tee_escape: false,
+            strip_ansi: false,
+            line_ending: LineEnding::Lf,
+            header: None,
+            footer: None,
+            #[cfg(unix)]
+            mode: None,
+            #[cfg(unix)]
+            owner: None,
+            scrub: Scrub::default(),
+            #[cfg(feature = "timezone")]
+            timezone: None,
+            precision: TimePrecision::Millis,
+            sequence: false,
+            hostname: false,
+            pid: false,
+            thread: false,
+            elapsed: false,
+            delta: false,
+            sync: false,
+            direct: None,
+            counters: Default::default(),
+        }
+    }
+
+    pub fn module(mut self, show: bool) -> Log2 {
+        self.module = show;
+        self
+    }
+
+    // split the output to stdout
+    pub fn tee(mut self, stdout: bool) -> Log2 {
+        self.tee = stdout;
+        self
+    }
+
+    /// setup the maximum size for each file
+    pub fn size(mut self, filesize: u64) -> Log2 {
+        if self.count <= 1 {
+            self.filesize = u64::MAX;
+        } else {
+            self.filesize = filesize;
+        }
+        self
+    }
+
+    /// setup the rotate count
+    pub fn rotate(mut self, count: usize) -> Log2 {
+        self.count = count;
+        if self.count <= 1 {
+            self.filesize = u64::MAX;
+        }
+        self
+    }
+
+    /// name the rotated files by index, the default, or by date
+    pub fn naming(mut self, naming: Naming) -> Log2 {
+        self.naming = naming;
+        self
+    }
+
+    /// rotate the file of the previous run at start, so every run begins a
+    /// fresh file, needs a `rotate()` of 2 or more
+    pub fn rotate_on_open(mut self, rotate: bool) -> Log2 {
+        self.rotate_on_open = rotate;
+        self
+    }
+
+    /// let several processes append to the same file, every write and
+    /// rotation holds an advisory lock on `<file>.lock`, so only one process
+    /// rotates and the others follow it to the new file
+    pub fn shared(mut self, shared: bool) -> Log2 {
+        self.shared = shared;
+        self
+    }
+
+    /// log to this file instead when the one of `open()` can't be created or
+    /// opened, e.g. a read-only filesystem, a warning notes the switch
+    pub fn fallback_path(mut self, path: impl AsRef<std::path::Path>) -> Log2 {
+        self.fallback = Some(path.as_ref().into());
+        self
+    }
+
+    /// keep a symlink pointing at the active file, also after a redirect or
+    /// a new directory of `template()`, for a stable path to `tail -F`
+    pub fn link_latest(mut self, link: impl AsRef<std::path::Path>) -> Log2 {
+        self.latest = Some(link.as_ref().into());
+        self
+    }
+
+    /// compress the rotated files to `log.1.txt.gz` on a background thread,
+    /// the worker goes on writing meanwhile, a build without the `gzip` and
+    /// `lz4` features leaves them uncompressed and warns at the start
+    pub fn compress(mut self, enable: bool) -> Log2 {
+        self.compress = enable;
+        self
+    }
+
+    /// gather the rotated files older than the days into monthly tarballs
+    /// like `log-2024-06.tar.gz` next to the active file, which saves inodes
+    /// and is easier to ship elsewhere
+    #[cfg(feature = "bundle")]
+    pub fn bundle(mut self, days: u32) -> Log2 {
+        self.bundle = Some(days);
+        self
+    }
+
+    /// end every line of the file with a HMAC-SHA256 of the key over the line
+    /// and the mac of the line before, `log2::verify()` finds edited, inserted
+    /// or removed lines, not for a `shared()` file
+    #[cfg(feature = "hmac")]
+    pub fn hmac(mut self, key: impl AsRef<[u8]>) -> Log2 {
+        self.hmac = Some(key.as_ref().to_vec());
+        self
+    }
+
+    /// log a warning once the volume of the file has less than `bytes` free,
+    /// checked every 10 seconds, again after it recovered
+    #[cfg(feature = "disk")]
+    pub fn low_disk(mut self, bytes: u64) -> Log2 {
+        self.low_disk = Some(bytes);
+        self
+    }
+
+    /// call back with the free bytes along with the warning of `low_disk()`,
+    /// e.g. to page someone or clean up
+    #[cfg(feature = "disk")]
+    pub fn on_low_disk(mut self, f: impl Fn(u64) + Send + Sync + 'static) -> Log2 {
+        self.on_low_disk = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// encrypt the rotated files with AES-256-GCM to `log.1.txt.enc`, after
+    /// `compress()` to `log.1.txt.gz.enc`, the active file stays plain,
+    /// `log2::decrypt()` reads them back
+    #[cfg(feature = "encrypt")]
+    pub fn encrypt_archives(mut self, key: [u8; 32]) -> Log2 {
+        self.encrypt = Some(key);
+        self
+    }
+
+    /// call back on a background thread with every rotated file, once it is
+    /// compressed or encrypted, e.g. to upload it, a file named by index is
+    /// renamed again by the next rotation
+    pub fn on_rotate(mut self, f: impl Fn(&std::path::Path) + Send + Sync + 'static) -> Log2 {
+        self.on_rotate = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// ask before an old archive is removed by `rotate()`, on the worker so
+    /// that the removal waits, e.g. for an upload to cold storage
+    pub fn on_prune(
+        mut self,
+        f: impl Fn(&std::path::Path) -> Decision + Send + Sync + 'static,
+    ) -> Log2 {
+        self.on_prune = Some(std::sync::Arc::new(f));
+        self
+    }
+
+    /// the codec of `compress()`, gzip unless only the `lz4` feature is on
+    #[cfg(any(feature = "gzip", feature = "lz4"))]
+    pub fn codec(mut self, codec: Codec) -> Log2 {
+        self.codec = codec;
+        self
+    }
+
+    /// name the rotated files, e.g. `|index, _| format!("app-{host}.{index}.log")`,
+    /// the index counts upwards and a taken name is skipped, relative names
+    /// are next to the active file, only the rotated files of this process
+    /// are removed once there are more than `rotate()` files
+    pub fn rotation_name(
+        mut self,
+        name: impl Fn(usize, std::time::SystemTime) -> String + Send + Sync + 'static,
+    ) -> Log2 {
+        self.rotation_name = Some(std::sync::Arc::new(naming::RotationName::new(name)));
+        self
+    }
+
+    /// a custom scheme deciding when the file is rotated, besides the size,
+    /// and naming the rotated files, needs a `rotate()` of 2 or more
+    pub fn rotation_policy(mut self, policy: impl RotationPolicy + 'static) -> Log2 {
+        let policy = std::sync::Arc::new(policy);
+        let names = policy.clone();
+        let name = move |index, time| names.next_name(index, time);
+        self.rotation_name = Some(std::sync::Arc::new(naming::RotationName::new(name)));
+        self.policy = Some(policy);
+        self
+    }
+
+    /// keep the files in memory, a test checks the size, rotation and naming
+    /// without touching the disk, the compression, `hmac()`, `link_latest()`
+    /// and `start_tokio()` still work on the disk, `mode()`, `owner()`,
+    /// `shared()` and `log2::template()` have no file on the disk to act on,
+    /// so `start()` panics with them
+    #[cfg(feature = "memory-fs")]
+    pub fn memory_fs(mut self, fs: MemoryFs) -> Log2 {
+        self.fs = std::sync::Arc::new(fs);
+        self
+    }
+
+    /// setup how often the file is flushed, default is 1s, zero flushes after every write
+    pub fn flush_interval(mut self, interval: std::time::Duration) -> Log2 {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// setup the write buffer capacity of the file, default is 64KB, zero writes through
+    pub fn buffer(mut self, capacity: usize) -> Log2 {
+        self.buffer = capacity;
+        self
+    }
+
+    /// write on the caller thread under a lock instead of a worker, every record
+    /// is flushed before the logging call returns
+    pub fn sync(mut self, sync: bool) -> Log2 {
+        self.sync = sync;
+        self
+    }
+
+    /// run each sink on its own thread, so a slow stdout can't stall the file
+    pub fn sharded(mut self, sharded: bool) -> Log2 {
+        self.sharded = sharded;
+        self
+    }
+
+    /// submit file writes through io_uring on linux, falls back to plain writes
+    /// on other systems or when the kernel doesn't support it
+    #[cfg(feature = "io-uring")]
+    pub fn io_uring(mut self, enable: bool) -> Log2 {
+        self.io_uring = enable;
+        self
+    }
+
+    /// append through a memory mapping reserved in chunks, the written part is
+    /// pushed out every flush interval, `sync_policy` decides when it is synced,
+    /// a crash may leave zeros after the last record, not with `shared()`
+    #[cfg(feature = "mmap")]
+    pub fn mmap(mut self, enable: bool) -> Log2 {
+        self.mmap = enable;
+        self
+    }
+
+    /// never block nor allocate in the logging call: records go through a ring of
+    /// preallocated slots, messages are cut at 512 bytes, enrichers are skipped,
+    /// and records are dropped and counted when the ring is full
+    pub fn non_blocking(mut self, capacity: usize) -> Log2 {
+        self.ring = Some(std::sync::Arc::new(ring::Ring::new(capacity)));
+        self
+    }
+
+    /// keep the last `n` records below the level in memory, they are written in
+    /// front of the next error or on `Handle::dump()`, needs `level()`
+    pub fn flight_recorder(mut self, n: usize) -> Log2 {
+        self.recorder = n;
+        self
+    }
+
+    /// keep the last `n` formatted lines in memory for `Handle::tail()`
+    pub fn recent(mut self, n: usize) -> Log2 {
+        self.recent = (n > 0).then(|| std::sync::Arc::new(Recent::new(n)));
+        self
+    }
+
+    /// setup when written records are synced to the disk, default is never
+    pub fn sync_policy(mut self, policy: SyncPolicy) -> Log2 {
+        self.sync_policy = policy;
+        self
+    }
+
+    /// retry writes and rotations failing with a transient error, e.g. on a
+    /// network filesystem, up to `attempts` times, waiting `backoff` first and
+    /// twice as long every time after, not for `start_tokio()`
+    pub fn retry(mut self, attempts: u32, backoff: std::time::Duration) -> Log2 {
+        self.retry = Retry { attempts, backoff };
+        self
+    }
+
+    /// provide a way to filter by module
+    pub fn module_filter(mut self, filter: impl Fn(&str) -> bool + Send + 'static) -> Log2 {
+        self.module_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// only log the modules matching one of the globs like `my_app::*`, a plain
+    /// name takes its submodules too
+    pub fn allow_modules<I, S>(mut self, modules: I) -> Log2
+    where
+        I: IntoIterator<Item = S>,
+        S: fmt::Display,
+    {
+        self.allow
+            .extend(modules.into_iter().map(|m| m.to_string()));
+        self
+    }
+
+    /// drop the modules matching one of the globs like `h2::*`, before the allowed ones
+    pub fn deny_modules<I, S>(mut self, modules: I) -> Log2
+    where
+        I: IntoIterator<Item = S>,
+        S: fmt::Display,
+    {
+        self.deny.extend(modules.into_iter().map(|m| m.to_string()));
+        self
+    }
+
+    /// filter by the whole record, e.g. level, target and message together
+    pub fn filter(mut self, filter: impl Fn(&Record) -> bool + Send + 'static) -> Log2 {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// render timestamps in a named zone like "America/New_York" instead of the
+    /// local time, panics if the zone is unknown
+    #[cfg(feature = "timezone")]
+    pub fn timezone(mut self, name: &str) -> Log2 {
+        let timezone = name.parse().expect("unknown timezone");
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// show micro or nanoseconds in the timestamps, default is milliseconds
+    pub fn precision(mut self, precision: TimePrecision) -> Log2 {
+        self.precision = precision;
+        self
+    }
+
+    /// number every record with a process-wide increasing sequence, a gap means
+    /// a dropped record
+    pub fn sequence(mut self, number: bool) -> Log2 {
+        self.sequence = number;
+        self
+    }
+
+    /// show the name of the machine after the timestamp
+    pub fn hostname(mut self, show: bool) -> Log2 {
+        self.hostname = show;
+        self
+    }
+
+    /// show the process id after the timestamp
+    pub fn pid(mut self, show: bool) -> Log2 {
+        self.pid = show;
+        self
+    }
+
+    /// show the name and the id of the logging thread after the timestamp
+    pub fn thread(mut self, show: bool) -> Log2 {
+        self.thread = show;
+        self
+    }
+
+    /// show the seconds since the start after the timestamp
+    pub fn elapsed(mut self, show: bool) -> Log2 {
+        self.elapsed = show;
+        self
+    }
+
+    /// show the seconds since the previous record after the timestamp
+    pub fn delta(mut self, show: bool) -> Log2 {
+        self.delta = show;
+        self
+    }
+
+    /// pick a built-in layout, `Format::Full` restores the default one
+    pub fn preset(mut self, format: Format) -> Log2 {
+        let format: Option<std::sync::Arc<dyn Formatter>> = match format {
+            Format::Full => None,
+            format => Some(std::sync::Arc::new(format)),
+        };
+        self.tee_format = format.clone();
+        self.file_format = format;
+        self
+    }
+
+    /// `ts=... level=info module=app msg="..."` on stdout and in the file,
+    /// the same as `preset(Format::Logfmt)`
+    pub fn logfmt(self) -> Log2 {
+        self.preset(Format::Logfmt)
+    }
+
+    /// replace the default layout of stdout and the file, colors included
+    pub fn format(mut self, format: impl Formatter + 'static) -> Log2 {
+        let format: std::sync::Arc<dyn Formatter> = std::sync::Arc::new(format);
+        self.tee_format = Some(format.clone());
+        self.file_format = Some(format);
+        self
+    }
+
+    /// recolor the default stdout layout, e.g. for light terminals
+    pub fn theme(mut self, theme: Theme) -> Log2 {
+        self.theme = theme;
+        self
+    }
+
+    /// rename a level in the default layouts, e.g. `WRN` for `Level::Warn`
+    pub fn label<T: fmt::Display>(mut self, level: Level, label: T) -> Log2 {
+        self.labels[level as usize] = label.to_string();
+        self
+    }
+
+    /// pad the level labels to the widest one, so the messages line up
+    pub fn align_levels(mut self, align: bool) -> Log2 {
+        self.align = align;
+        self
+    }
+
+    /// indent or prefix the continuation lines of multi-line messages
+    pub fn continuation(mut self, continuation: Continuation) -> Log2 {
+        self.continuation = continuation;
+        self
+    }
+
+    /// replace the matches of `pattern` in every message before any output sees
+    /// it, e.g. tokens or card numbers, `$1` refers to groups, panics if the
+    /// pattern is invalid
+    #[cfg(feature = "redact")]
+    pub fn redact(mut self, pattern: &str, replacement: &str) -> Log2 {
+        let regex = regex::Regex::new(pattern).expect("invalid redact pattern");
+        self.scrub.redactions.push((regex, replacement.to_string()));
+        self
+    }
+
+    /// cut messages longer than `len` bytes, with a `…[truncated N bytes]` marker,
+    /// default is 0 for unlimited
+    pub fn max_message_len(mut self, len: usize) -> Log2 {
+        self.scrub.max_message_len = len;
+        self
+    }
+
+    /// escape the control characters of messages in the file, so untrusted
+    /// input can't forge lines, default is true, newlines stay with a continuation
+    pub fn escape(mut self, escape: bool) -> Log2 {
+        self.escape = escape;
+        self
+    }
+
+    /// remove the ANSI escapes of the file lines, e.g. of a colored formatter
+    /// shared with stdout, stdout keeps them
+    pub fn strip_ansi(mut self, strip: bool) -> Log2 {
+        self.strip_ansi = strip;
+        self
+    }
+
+    /// permissions of the log files like `0o600`, applied whenever a file is
+    /// opened, the rotated files keep them
+    #[cfg(unix)]
+    pub fn mode(mut self, mode: u32) -> Log2 {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// owner and group of the log files, changing them needs the privileges
+    #[cfg(unix)]
+    pub fn owner(mut self, uid: u32, gid: u32) -> Log2 {
+        self.owner = Some((uid, gid));
+        self
+    }
+
+    /// write a line like a banner with the version at the top of the file
+    /// whenever it is opened, at start, after a redirect and after a rotation
+    pub fn header(mut self, header: impl Fn() -> String + Send + Sync + 'static) -> Log2 {
+        self.header = Some(std::sync::Arc::new(header));
+        self
+    }
+
+    /// write a last line into a file before it is rotated away, so every
+    /// rotated file tells its size and records
+    pub fn footer(mut self, footer: impl Fn(&Trailer) -> String + Send + Sync + 'static) -> Log2 {
+        self.footer = Some(std::sync::Arc::new(footer));
+        self
+    }
+
+    /// end the lines of the file with `\n`, the default, or `\r\n`
+    pub fn line_ending(mut self, ending: LineEnding) -> Log2 {
+        self.line_ending = ending;
+        self
+    }
+
+    /// escape the control characters of messages on stdout, so untrusted input
+    /// can't move the cursor or recolor the terminal, default is false
+    pub fn tee_escape(mut self, escape: bool) -> Log2 {
+        self.tee_escape = escape;
+        self
+    }
+
+    /// color stdout on a terminal only by default, or always, or never
+    pub fn color(mut self, color: ColorChoice) -> Log2 {
+        self.color = color;
+        self
+    }
+
+    /// replace the layout of stdout only, the file keeps its own
+    pub fn tee_format(mut self, format: impl Formatter + 'static) -> Log2 {
+        self.tee_format = Some(std::sync::Arc::new(format));
+        self
+    }
+
+    /// replace the layout of the file and the tail window only, stdout keeps its own
+    pub fn file_format(mut self, format: impl Formatter + 'static) -> Log2 {
+        self.file_format = Some(std::sync::Arc::new(format));
+        self
+    }
+
+    /// append an enricher to the chain, enrichers run in order before formatting
+    pub fn enrich(mut self, enricher: impl Enricher + 'static) -> Log2 {
+        self.enrichers.push(Box::new(enricher));
+        self
+    }
+
+    /// append a layer to the chain, layers run in order after the enrichers and
+    /// may change the level, the module or the message, or drop the record,
+    /// with `non_blocking()` they run on the worker
+    pub fn layer(mut self, layer: impl Layer + 'static) -> Log2 {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    pub fn level<T: fmt::Display>(mut self, name: T) -> Self {
+        self.level = Some(get_level(name.to_string()));
+        self
+    }
+
+    /// the level of stdout, below the global one it keeps the console quiet
+    pub fn tee_level<T: fmt::Display>(mut self, name: T) -> Self {
+        self.tee_level = Some(get_level(name.to_string()));
+        self
+    }
+
+    /// the level of the file and the tail window, the global level follows
+    /// the wider of the two outputs
+    pub fn file_level<T: fmt::Display>(mut self, name: T) -> Self {
+        self.file_level = Some(get_level(name.to_string()));
+        self
+    }
+
+    /// bytes the file may take per day, beyond them only warnings and errors
+    /// are written until midnight, which is noted once in the file
+    pub fn daily_quota(mut self, bytes: u64) -> Log2 {
+        self.daily_quota = Some(bytes);
+        self
+    }
+
+    /// the global level, wide enough for both stdout and the file
+    fn threshold(&self) -> Option<LevelFilter> {
+        if self.level.is_none() && self.tee_level.is_none() && self.file_level.is_none() {
+            return None;
+        }
+        let level = self.level.unwrap_or(LevelFilter::Trace);
+        let tee = self.tee_level.unwrap_or(level);
+        let file = self.file_level.unwrap_or(level);
+        Some(tee.max(file))
+    }
+
+    /// a record of the level is logged, or kept by the flight recorder
+    fn wants(&self, level: Level, target: &str) -> bool {
+        level <= level_of(target) || self.recording()
+    }
+
+    /// the flight recorder keeps the records below the level
+    fn recording(&self) -> bool {
+        self.recorder > 0 && self.ring.is_none() && self.direct.is_none()
+    }
+
+    /// the module passes `module_filter()`, `allow()`, `deny()` and the
+    /// modules of the config
+    fn admits(&self, module: &str) -> bool {
+        if let Some(filter) = &self.module_filter {
+            if !filter(module) {
+                return false;
+            }
+        }
+        if let Some(modules) = &self.modules {
+            if let Ok(modules) = modules.read() {
+                if !modules.is_empty() && !modules.iter().any(|m| matches(module, m)) {
+                    return false;
+                }
+            }
+        }
+        if self.deny.iter().any(|m| matches(module, m)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|m| matches(module, m))
+    }
+
+    /// setup the exit code used by `fatal!`, default is 1
+    pub fn fatal_code(mut self, code: i32) -> Log2 {
+        self.fatal_code = code;
+        self
+    }
+
+    /// write panics with their backtraces to the log before unwinding
+    pub fn capture_panics(mut self, capture: bool) -> Log2 {
+        self.capture_panics = capture;
+        self
+    }
+
+    /// redirect stdout and stderr of the process into the log, lines of
+    /// stdout at info and of stderr at warn, with `stdout` and `stderr` as
+    /// targets, the tee is off as it would write into the log again
+    #[cfg(unix)]
+    pub fn capture_std_streams(mut self, capture: bool) -> Log2 {
+        self.std_streams = capture;
+        self
+    }
+
+    /// send the records to the unified logging of apple as well, shown by
+    /// Console.app, the crate of the module is the subsystem and the rest of
+    /// the module path the category, not with `non_blocking()`
+    #[cfg(all(feature = "oslog", target_vendor = "apple"))]
+    pub fn os_log(mut self, enable: bool) -> Log2 {
+        self.os_log = enable;
+        self
+    }
+
+    /// the records go to the unified logging of apple only
+    fn os_log_only(&self) -> bool {
+        #[cfg(all(feature = "oslog", target_vendor = "apple"))]
+        return self.os_log && !self.tee && self.path.as_os_str().is_empty() && !self.capture;
+        #[cfg(not(all(feature = "oslog", target_vendor = "apple")))]
+        false
+    }
+
+    /// apply `LOG2_FILE`, `LOG2_LEVEL`, `LOG2_SIZE`, `LOG2_ROTATE`, `LOG2_TEE` and `LOG2_COLOR`,
+    /// the builder values stay the defaults, invalid values are ignored
+    fn env(mut self) -> Log2 {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        if let Some(path) = std::env::var_os("LOG2_FILE").filter(|v| !v.is_empty()) {
+            let _ = create(path.as_ref());
+            self.path = path.into();
+        }
+        if let Some(level) = var("LOG2_LEVEL") {
+            self.level = Some(get_level(level));
+        }
+        // rotate first, size is ignored for a single file
+        if let Some(count) = var("LOG2_ROTATE").and_then(|v| v.trim().parse().ok()) {
+            self = self.rotate(count);
+        }
+        if let Some(size) = var("LOG2_SIZE").and_then(|v| v.trim().parse().ok()) {
+            self = self.size(size);
+        }
+        match var("LOG2_TEE").map(|v| v.trim().to_lowercase()).as_deref() {
+            Some("1" | "true" | "yes" | "on") => self.tee = true,
+            Some("0" | "false" | "no" | "off") => self.tee = false,
+            _ => {}
+        }
+        match var("LOG2_COLOR")
+            .map(|v| v.trim().to_lowercase())
+            .as_deref()
+        {
+            Some("always") => self.color = ColorChoice::Always,
+            Some("never") => self.color = ColorChoice::Never,
+            Some("auto") => self.color = ColorChoice::Auto,
+            _ => {}
+        }
+        self
+    }
+
+    /// switch to `fallback_path()` when the file can't be created, the
+    /// warning about it is returned, panic without a fallback
+    fn fallback(&mut self) -> Option<String> {
+        if self.path.as_os_str().is_empty() {
+            return None;
+        }
+        let e = self.fs.create(&self.path).err()?;
+        let fallback = self.fallback.take().expect("error to open file");
+        self.fs.create(&fallback).expect("error to open file");
+        let message = format!(
+            "failed to open {}: {e}, logging to {} instead",
+            self.path.display(),
+            fallback.display()
+        );
+        self.path = fallback;
+        Some(message)
+    }
+
+    /// the warning of `compress()` in a build without a codec
+    fn uncompressed(&self) -> Option<String> {
+        let codec = cfg!(any(feature = "gzip", feature = "lz4"));
+        let message =
+            "compress() needs the gzip or lz4 feature, the rotated files stay uncompressed";
+        (self.compress && !codec).then(|| message.into())
+    }
+
+    /// the permissions, the locks and the directories of a template are set on
+    /// the disk, `memory_fs()` would leave them behind there
+    fn on_disk(&self) {
+        #[cfg(unix)]
+        let disk = self.mode.is_some() || self.owner.is_some();
+        #[cfg(not(unix))]
+        let disk = false;
+        assert!(
+            !self.fs.memory() || !(disk || self.shared || self.template.is_some()),
+            "mode(), owner(), shared() and template() act on the disk, they don't work with memory_fs()"
+        );
+    }
+
+    /// the task can't be waited for on the only thread of the runtime
+    #[cfg(feature = "tokio")]
+    fn current_thread() -> Option<String> {
+        let flavor = tokio::runtime::Handle::current().runtime_flavor();
+        let message = "start_tokio() on a current-thread runtime, flush(), fatal! and \
+            the panic hook don't wait for the records, use flush_async()";
+        (flavor == tokio::runtime::RuntimeFlavor::CurrentThread).then(|| message.into())
+    }
+
+    /// start the log2 instance
+    pub fn start(self) -> Handle {
+        let mut logger = self.env();
+        logger.on_disk();
+        let warnings = [logger.fallback(), logger.uncompressed()];
+        FATAL_CODE.store(logger.fatal_code, Ordering::Relaxed);
+        let threshold = logger.threshold();
+        let handle = start_log2(logger);
+        if let Some(level) = threshold {
+            set_level(level);
+        }
+        for message in warnings.into_iter().flatten() {
+            report(&handle.tx, &handle.counters, Level::Warn, message);
+        }
+        handle
+    }
+
+    /// start the log2 instance as a task of the current tokio runtime instead
+    /// of a thread, panics when called outside of a runtime, the waiting
+    /// `flush()`, `shutdown()`, `fatal!` and the panic hook of
+    /// `capture_panics()` need a multi-thread runtime, and so do `shared()`
+    /// and `on_prune()`, which block the task, it panics without one then
+    #[cfg(feature = "tokio")]
+    pub fn start_tokio(self) -> Handle {
+        let flavor = tokio::runtime::Handle::current().runtime_flavor();
+        if flavor == tokio::runtime::RuntimeFlavor::CurrentThread {
+            assert!(
+                !self.shared && self.on_prune.is_none(),
+                "shared() and on_prune() block the runtime, start_tokio() needs a multi-thread runtime for them"
+            );
+        }
+        let mut logger = self.env();
+        logger.on_disk();
+        let warnings = [
+            logger.fallback(),
+            logger.uncompressed(),
+            Self::current_thread(),
+        ];
+        FATAL_CODE.store(logger.fatal_code, Ordering::Relaxed);
+        let threshold = logger.threshold();
+        let handle = start_task(logger);
+        if let Some(level) = threshold {
+            set_level(level);
+        }
+        for message in warnings.into_iter().flatten() {
+            report(&handle.tx, &handle.counters, Level::Warn, message);
+        }
+        handle
+    }
+}
+
+impl Default for Log2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Sync for Log2 {}
+
+impl log::Log for Log2 {
+    /// by the level and the module filters, the target stands in for the
+    /// module, which it is unless set
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let target = metadata.target();
+        self.wants(metadata.level(), target) && self.admits(target)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.tee && self.path.as_os_str().is_empty() && !self.capture && !self.os_log_only() {
+            return;
+        }
+
+        let time = now();
+        let module = record.module_path().unwrap_or("unknown");
+
+        if !self.admits(module) {
+            return;
+        }
+        if let Some(filter) = &self.filter {
+            if !filter(record) {
+                return;
+            }
+        }
+
+        // below the level only the flight recorder wants the record
+        let remember = record.level() > level_of(record.target());
+        if remember && !self.recording() {
+            return;
+        }
+
+        let counters = &self.counters;
+        if !remember {
+            counters.record(record.level());
+        }
+        let sequence = match self.sequence {
+            true => next_sequence(),
+            false => 0,
+        };
+        let thread = self.thread.then(Thread::current);
+
+        // preallocated ring, enrichers are skipped since they allocate
+        if let Some(ring) = &self.ring {
+            let pushed = ring.push(|frame| {
+                frame.sequence = sequence;
+                frame.thread = thread;
+                frame.level = record.level();
+                frame.set_module(module);
+                frame.time = time;
+                let _ = fmt::Write::write_fmt(frame, *record.args());
+            });
+            match pushed {
+                true => counters.queued(),
+                false => counters.dropped(),
+            }
+            return;
+        }
+
+        // enrich
+        let mut message = self.pool.take();
+        let _ = fmt::Write::write_fmt(&mut message, *record.args());
+        for enricher in &self.enrichers {
+            for (key, value) in enricher.enrich(record) {
+                let _ = fmt::Write::write_fmt(&mut message, format_args!(" {key}={value}"));
+            }
+        }
+
+        let mut view = RecordView {
+            level: record.level(),
+            module: match record.module_path_static() {
+                Some(module) => module.into(),
+                None => module.to_string().into(),
+            },
+            message,
+        };
+        for layer in &self.layers {
+            match layer.layer(view) {
+                Some(next) => view = next,
+                None => return,
+            }
+        }
+        let RecordView {
+            level,
+            module,
+            mut message,
+        } = view;
+
+        self.scrub.apply(&mut message);
+
+        #[cfg(all(feature = "oslog", target_vendor = "apple"))]
+        if self.os_log && !remember {
+            unified::log(level, &module, &message);
+        }
+        if self.os_log_only() {
+            self.pool.give(message);
+            return;
+        }
+
+        if self.capture {
+            if let Ok(mut records) = CAPTURED.lock() {
+                records.push(Captured {
+                    level,
+                    module: module.to_string(),
+                    message: message.clone(),
+                });
+            }
+            if !self.tee && self.path.as_os_str().is_empty() {
+                self.pool.give(message);
+                return;
+            }
+        }
+
+        // the worker formats the record
+        let entry = Entry {
+            sequence,
+            thread,
+            level,
+            module,
+            time,
+            message,
+            raw: false,
+        };
+        // written right here, the lock keeps the records in order
+        if let Some(direct) = &self.direct {
+            counters.queued();
+            if let Ok(mut direct) = direct.lock() {
+                if let Err(e) = direct.write(entry) {
+                    counters.failed();
+                    direct.report(Level::Error, format!("failed to write a record: {e}"));
+                }
+            }
+            return;
+        }
+
+        let action = match remember {
+            true => Action::Remember(entry),
+            false => Action::Record(entry),
+        };
+        counters.queued();
+        if !self.tx.send(action) {
+            counters.handled();
+            counters.dropped();
+        }
+    }
+
+    /// wait until all queued records are written and flushed
+    fn flush(&self) {
+        flush(&self.tx);
+    }
+}
+
+impl Handle {
+    /// a controller to share with other components, stopping stays with the handle
+    pub fn downgrade(&self) -> Controller {
+        Controller {
+            tx: self.tx.clone(),
+            counters: self.counters.clone(),
+            recent: self.recent.clone(),
+            direct: self.direct.clone(),
+        }
+    }
+
+    /// stop the worker once it wrote out the queued records, dropping the
+    /// handle stops it too, inside a current-thread tokio runtime neither
+    /// waits for the task, use `stop_async` there
+    pub fn stop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.tx.send(Action::Exit);
+            let _ = thread.join();
+        }
+        // the task can't be joined here, waiting for its flush has to do
+        #[cfg(feature = "tokio")]
+        if self.task.take().is_some() {
+            flush(&self.tx);
+            self.tx.send(Action::Exit);
+        }
+    }
+
+    /// stop the task of `start_tokio()` and wait until it wrote out the
+    /// queued records, e.g. at the end of an async main
+    #[cfg(feature = "tokio")]
+    pub async fn stop_async(&mut self) {
+        if let Some(task) = self.task.take() {
+            self.tx.send(Action::Exit);
+            let _ = task.await;
+        }
+        self.stop();
+    }
+
+    pub fn set_level<T: fmt::Display>(&self, level: T) {
+        crate::set_level(level);
+    }
+
+    /// the effective level, after the builder, `LOG2_LEVEL` and `set_level()`
+    pub fn level(&self) -> LevelFilter {
+        self.downgrade().level()
+    }
+
+    /// replace the whole pipeline, sinks, filters and rotation included, with a
+    /// new configuration, records queued so far are written by the old worker
+    pub fn reconfigure(&mut self, logger: Log2) {
+        let handle = logger.start();
+        drop(std::mem::replace(self, handle));
+    }
+
+    /// wait until all queued records are written and flushed, inside a
+    /// current-thread tokio runtime this only requests a flush, use
+    /// `flush_async` to wait for it there
+    pub fn flush(&self) {
+        flush(&self.tx);
+    }
+
+    /// wait until all queued records are written and flushed
+    #[cfg(feature = "tokio")]
+    pub async fn flush_async(&self) {
+        let (done, wait) = tokio::sync::oneshot::channel();
+        let done = Box::new(move || {
+            let _ = done.send(());
+        });
+        if self.tx.send(Action::Flush(done)) {
+            let _ = wait.await;
+        }
+    }
+
+    /// redirect the output file
+    pub fn redirect(&mut self, path: impl AsRef<std::path::Path>) {
+        let path = path.as_ref();
+        create(path).expect("error to open file");
+
+        // redirect log file
+        self.path = path.into();
+        self.template = None;
+        if let Some(direct) = &self.direct {
+            if let Ok(mut direct) = direct.lock() {
+                if let Err(e) = direct.redirect(path.into()) {
+                    let message = format!("failed to redirect to {}: {e}", path.display());
+                    direct.report(Level::Error, message);
+                }
+            }
+            return;
+        }
+        self.tx.send(Action::Redirect(path.into()));
+    }
+
+    /// switch mirroring to stdout on or off, records logged before are not affected
+    pub fn set_tee(&self, stdout: bool) {
+        self.downgrade().set_tee(stdout);
+    }
+
+    /// replace the formatter, records logged before keep the previous layout
+    pub fn set_format(&self, format: impl Formatter + 'static) {
+        self.downgrade().set_format(format);
+    }
+
+    /// change the file size and the rotate count, the next record rotates if the
+    /// active file is over the new size already
+    pub fn set_rotation(&mut self, filesize: u64, count: usize) {
+        self.count = count;
+        self.downgrade().set_rotation(filesize, count);
+    }
+
+    /// apply changes of the config file live: level, tee and modules, the
+    /// modules only when the logger was built by `from_config()`
+    #[cfg(feature = "watch")]
+    pub fn watch(&mut self, path: &str) -> Result<(), std::io::Error> {
+        let counters = self.counters.clone();
+        let watcher = config::watch(path, self.tx.clone(), counters, self.modules.clone())?;
+        self.watcher = Some(watcher);
+        Ok(())
+    }
+
+    /// flush and pack the active file and the rotated files into a zip archive
+    #[cfg(feature = "zip")]
+    pub fn export_zip(&self, path: impl AsRef<std::path::Path>) -> Result<(), std::io::Error> {
+        self.flush();
+
+        let file = std::fs::File::create(path)?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for segment in self.segments() {
+            let name = segment
+                .path
+                .file_name()
+                .unwrap_or(segment.path.as_os_str())
+                .to_string_lossy()
+                .to_string();
+            zip.start_file(name, options)?;
+            let mut source = std::fs::File::open(&segment.path)?;
+            std::io::copy(&mut source, &mut zip)?;
+        }
+        zip.finish()?;
+
+        Ok(())
+    }
+
+    /// the last `n` formatted lines, oldest first, see `Log2::recent()`
+    pub fn tail(&self, n: usize) -> Vec<String> {
+        self.downgrade().tail(n)
+    }
+
+    /// write out the records kept by the flight recorder
+    pub fn dump(&self) {
+        self.downgrade().dump();
+    }
+
+    /// append a line formatted elsewhere to the file as it is, e.g. from a C
+    /// library, in order with the records and counted towards the rotation
+    pub fn write_line(&self, line: &str) {
+        self.downgrade().write_line(line);
+    }
+
+    /// records waiting for the worker
+    pub fn queue_len(&self) -> usize {
+        self.downgrade().queue_len()
+    }
+
+    /// the worker is running and the log file, if any, exists and can be
+    /// opened for writing, checking never creates it
+    pub fn healthy(&self) -> bool {
+        let mut alive = self.direct.is_some();
+        if let Some(thread) = &self.thread {
+            alive = !thread.is_finished();
+        }
+        #[cfg(feature = "tokio")]
+        if let Some(task) = &self.task {
+            alive = !task.is_finished();
+        }
+        if !alive {
+            return false;
+        }
+        if self.path.as_os_str().is_empty() {
+            return true;
+        }
+        self.fs.writable(&self.path)
+    }
+
+    /// records lost because the queue was full or gone, see `non_blocking`
+    pub fn dropped(&self) -> u64 {
+        self.downgrade().dropped()
+    }
+
+    /// counts per level, bytes written, rotations, queue depth and drops since start
+    pub fn stats(&self) -> Stats {
+        self.downgrade().stats()
+    }
+
+    /// list the active file and the rotated files, newest first
+    pub fn segments(&self) -> Vec<Segment> {
+        if self.path.as_os_str().is_empty() {
+            return Vec::new();
+        }
+        let path = match &self.template {
+            Some(template) => naming::expand(template),
+            None => self.path.clone(),
+        };
+        let archives = naming::archives(
+            &*self.fs,
+            &path,
+            self.count,
+            self.naming,
+            self.rotation_name.as_deref(),
+        );
+        std::iter::once(path)
+            .chain(archives)
+            .filter_map(|path| {
+                // or its compressed file of `compress()`
+                let path = compress::existing(path);
+                let meta = self.fs.metadata(&path).ok()?;
+                Some(Segment {
+                    size: meta.len,
+                    modified: meta.modified,
+                    path,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Controller {
+    pub fn set_level<T: fmt::Display>(&self, level: T) {
+        crate::set_level(level);
+    }
+
+    /// the effective level, after the builder, `LOG2_LEVEL` and `set_level()`
+    pub fn level(&self) -> LevelFilter {
+        current_level()
+    }
+
+    /// wait until all queued records are written and flushed, inside a
+    /// current-thread tokio runtime this only requests a flush
+    pub fn flush(&self) {
+        flush(&self.tx);
+    }
+
+    /// switch mirroring to stdout on or off, records logged before are not affected
+    pub fn set_tee(&self, stdout: bool) {
+        if let Some(direct) = &self.direct {
+            if let Ok(mut direct) = direct.lock() {
+                direct.ctx.tee = stdout;
+            }
+            return;
+        }
+        self.tx.send(Action::Tee(stdout));
+    }
+
+    /// replace the formatter, records logged before keep the previous layout
+    pub fn set_format(&self, format: impl Formatter + 'static) {
+        let format: std::sync::Arc<dyn Formatter> = std::sync::Arc::new(format);
+        if let Some(direct) = &self.direct {
+            if let Ok(mut direct) = direct.lock() {
+                direct.ctx.tee_format = Some(format.clone());
+                direct.ctx.file_format = Some(format);
+            }
+            return;
+        }
+        self.tx.send(Action::Format(format));
+    }
+
+    /// change the file size and the rotate count, the next record rotates if the
+    /// active file is over the new size already
+    pub fn set_rotation(&self, filesize: u64, count: usize) {
+        let filesize = if count <= 1 { u64::MAX } else { filesize };
+        if let Some(direct) = &self.direct {
+            if let Ok(mut direct) = direct.lock() {
+                direct.ctx.size = filesize;
+                direct.ctx.count = count;
+            }
+            return;
+        }
+        self.tx.send(Action::Rotation(filesize, count));
+    }
+
+    /// the last `n` formatted lines, oldest first, see `Log2::recent()`
+    pub fn tail(&self, n: usize) -> Vec<String> {
+        match &self.recent {
+            Some(recent) => recent.tail(n),
+            None => Vec::new(),
+        }
+    }
+
+    /// write out the records kept by the flight recorder
+    pub fn dump(&self) {
+        self.tx.send(Action::Dump);
+    }
+
+    /// append a line formatted elsewhere to the file as it is, e.g. from a C
+    /// library, in order with the records and counted towards the rotation
+    pub fn write_line(&self, line: &str) {
+        let entry = Entry {
+            sequence: 0,
+            thread: None,
+            level: Level::Info,
+            module: std::borrow::Cow::Borrowed(""),
+            time: now(),
+            message: line.to_string(),
+            raw: true,
+        };
+        let counters = &self.counters;
+        counters.queued();
+        if let Some(direct) = &self.direct {
+            if let Ok(mut direct) = direct.lock() {
+                if let Err(e) = direct.write(entry) {
+                    counters.failed();
+                    direct.report(Level::Error, format!("failed to write a record: {e}"));
+                }
+            }
+            return;
+        }
+        if !self.tx.send(Action::Record(entry)) {
+            counters.handled();
+            counters.dropped();
+        }
+    }
+
+    /// records waiting for the worker
+    pub fn queue_len(&self) -> usize {
+        self.counters.queued.load(Ordering::Relaxed) as usize
+    }
+
+    /// records lost because the queue was full or gone, see `non_blocking`
+    pub fn dropped(&self) -> u64 {
+        self.counters.dropped.load(Ordering::Relaxed)
+    }
+
+    /// counts per level, bytes written, rotations, queue depth and drops since start
+    pub fn stats(&self) -> Stats {
+        let counters = &self.counters;
+        let records = |level: Level| counters.records[level as usize].load(Ordering::Relaxed);
+        Stats {
+            error: records(Level::Error),
+            warn: records(Level::Warn),
+            info: records(Level::Info),
+            debug: records(Level::Debug),
+            trace: records(Level::Trace),
+            bytes: counters.bytes.load(Ordering::Relaxed),
+            rotations: counters.rotations.load(Ordering::Relaxed),
+            queued: counters.queued.load(Ordering::Relaxed),
+            dropped: counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn rotate(ctx: &mut Context) -> Result<fs::File, std::io::Error> {
+    retarget(ctx)?;
+    let retry = ctx.retry;
+    #[cfg(feature = "mmap")]
+    if ctx.mmap && !ctx.shared {
+        if let Err(e) = mapped::trim(&ctx.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                ctx.report(
+                    Level::Warn,
+                    format!("failed to trim {}: {e}", ctx.path.display()),
+                );
+            }
+        }
+    }
+    let size = retry.run(|| ctx.fs.metadata(&ctx.path))?.len;
+    let fresh = ctx.fresh.swap(false, Ordering::Relaxed) && size > 0;
+
+    if size >= ctx.size || fresh {
+        if let Some(footer) = footer(ctx, size) {
+            let mut file = retry.run(|| ctx.fs.open(&ctx.path, None))?;
+            file.write_all(footer.as_bytes())?;
+            ctx.counters.written(footer.len() as u64);
+        }
+        shift(ctx);
+        ctx.counters.rotated();
+    }
+
+    #[cfg(unix)]
+    let mode = ctx.mode;
+    #[cfg(not(unix))]
+    let mode = None;
+    let mut file = retry.run(|| ctx.fs.open(&ctx.path, mode))?;
+    ctx.records.store(0, Ordering::Relaxed);
+    ctx.opened = now();
+    if let Some(file) = file.disk() {
+        opened(ctx, &file.metadata()?);
+    }
+    protect(ctx)?;
+    link(ctx);
+    resume(ctx)?;
+    if let Some(header) = header(ctx) {
+        file.write_all(header.as_bytes())?;
+        ctx.counters.written(header.len() as u64);
+    }
+
+    Ok(file)
+}
+
+/// point the symlink of `Log2::link_latest()` at the active file, a failure
+/// is reported and logging goes on
+fn link(ctx: &Context) {
+    let Some(latest) = &ctx.latest else {
+        return;
+    };
+    if let Err(e) = relink(latest, &ctx.path) {
+        let message = format!(
+            "failed to link {} to {}: {e}",
+            latest.display(),
+            ctx.path.display()
+        );
+        ctx.report(Level::Warn, message);
+    }
+}
+
+/// replace the link at once, a reader never finds it missing
+fn relink(latest: &std::path::Path, path: &std::path::Path) -> Result<(), std::io::Error> {
+    let target = std::path::absolute(path)?;
+    if std::fs::read_link(latest).is_ok_and(|t| t == target) {
+        return Ok(());
+    }
+    let mut temp = latest.as_os_str().to_owned();
+    temp.push(".tmp");
+    let _ = std::fs::remove_file(&temp);
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, &temp)?;
+    #[cfg(windows)]
+    std::os::windows::fs::symlink_file(&target, &temp)?;
+    #[cfg(not(any(unix, windows)))]
+    return Err(std::io::ErrorKind::Unsupported.into());
+    #[cfg(any(unix, windows))]
+    std::fs::rename(&temp, latest)
+}
+
+/// the lock of `Log2::shared()`, held until it is dropped, none for a file of
+/// this process only
+fn lock(ctx: &Context) -> Result<Option<std::fs::File>, std::io::Error> {
+    if !ctx.shared || ctx.path.as_os_str().is_empty() {
+        return Ok(None);
+    }
+    let mut name = ctx.path.as_os_str().to_owned();
+    name.push(".lock");
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(name)?;
+    lock.lock()?;
+    Ok(Some(lock))
+}
+
+#[cfg(unix)]
+fn opened(ctx: &mut Context, meta: &std::fs::Metadata) {
+    use std::os::unix::fs::MetadataExt;
+    ctx.inode = (meta.dev(), meta.ino());
+}
+
+#[cfg(not(unix))]
+fn opened(_: &mut Context, _: &std::fs::Metadata) {}
+
+/// the active file is still at its path, another process of `Log2::shared()`
+/// might have rotated it away
+#[cfg(unix)]
+fn current(ctx: &Context) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(&ctx.path).is_ok_and(|meta| (meta.dev(), meta.ino()) == ctx.inode)
+}
+
+#[cfg(not(unix))]
+fn current(_: &Context) -> bool {
+    true
+}
+
+/// write the pending lines of a shared file under the lock, follow another
+/// process to its new file first and rotate a full one, the size is returned
+fn append(
+    ctx: &mut Context,
+    target: &mut Option<Target>,
+    lines: &mut Vec<String>,
+) -> Result<u64, std::io::Error> {
+    let _lock = lock(ctx)?;
+    if !current(ctx) {
+        let file = rotate(ctx)?;
+        *target = Some(writer(ctx, file));
+    }
+    let Some(file) = target.as_mut() else {
+        return Ok(0);
+    };
+    write_lines(file, lines, ctx)?;
+    file.flush()?;
+    let size = std::fs::metadata(&ctx.path)?.len();
+    if !due(ctx, size) {
+        return Ok(size);
+    }
+    if ctx.sync != SyncPolicy::Never {
+        sync(file)?;
+    }
+    let file = rotate(ctx)?;
+    let size = filled(ctx, file.len()?);
+    *target = Some(writer(ctx, file));
+    Ok(size)
+}
+
+/// the renames and removals of a rotation
+fn shift(ctx: &mut Context) {
+    let pause = ctx.compressor.as_ref().map(|c| c.pause());
+    let moves = naming::moves(ctx);
+    for (a, b) in &moves {
+        let result = match b {
+            Some(b) => ctx.retry.run(|| ctx.fs.rename(a, b)),
+            None => prune(ctx, a),
+        };
+        if let Err(e) = result {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                ctx.report(Level::Error, moved(a, b.as_deref(), e));
+            }
+        }
+    }
+    drop(pause);
+    compress(ctx, &moves);
+}
+
+/// remove an old archive, unless `Log2::on_prune()` keeps it
+fn prune(ctx: &Context, path: &std::path::Path) -> Result<(), std::io::Error> {
+    let decision = match &ctx.on_prune {
+        Some(on_prune) if ctx.fs.exists(path) => on_prune(path),
+        _ => Decision::Delete,
+    };
+    match decision {
+        Decision::Delete => ctx.fs.remove(path),
+        Decision::Keep => ctx.fs.rename(path, &naming::dated(&*ctx.fs, path)),
+    }
+}
+
+/// hand the rotated files to the compression, not only the one just rotated
+/// away, as one still queued has been renamed by this rotation, and to the
+/// bundling and `Log2::on_rotate()`
+fn compress(ctx: &mut Context, moves: &[(std::path::PathBuf, Option<std::path::PathBuf>)]) {
+    let Some(compressor) = &mut ctx.compressor else {
+        return;
+    };
+    for (a, rotated) in moves {
+        let Some(rotated) = rotated else {
+            continue;
+        };
+        let queue = match compressor.archiving() {
+            true => !compress::compressed(rotated) && rotated.exists(),
+            false => *a == ctx.path,
+        };
+        if queue {
+            compressor.compress(rotated.clone());
+        }
+    }
+    #[cfg(feature = "bundle")]
+    {
+        let custom = ctx.rotation_name.as_deref();
+        let archives = naming::archives(&*ctx.fs, &ctx.path, ctx.count, ctx.naming, custom);
+        compressor.gather(ctx.path.clone(), archives);
+    }
+}
+
+/// the line of `Log2::header()` with its line ending
+fn header(ctx: &Context) -> Option<String> {
+    let line = (ctx.header.as_ref()?)();
+    Some(ending(ctx, line))
+}
+
+/// the current time, the clock of the browser on wasm, where the one of std panics
+fn now() -> std::time::SystemTime {
+    #[cfg(all(feature = "console", target_arch = "wasm32"))]
+    return std::time::UNIX_EPOCH
+        + std::time::Duration::from_secs_f64(web_sys::js_sys::Date::now() / 1000.0);
+    #[cfg(not(all(feature = "console", target_arch = "wasm32")))]
+    std::time::SystemTime::now()
+}
+
+/// the line of `Log2::footer()` for a file of `bytes` with its line ending
+fn footer(ctx: &Context, bytes: u64) -> Option<String> {
+    let trailer = Trailer {
+        time: now(),
+        bytes,
+        records: ctx.records.load(Ordering::Relaxed),
+    };
+    let line = (ctx.footer.as_ref()?)(&trailer);
+    Some(ending(ctx, line))
+}
+
+fn ending(ctx: &Context, mut line: String) -> String {
+    line.push('\n');
+    if ctx.line_ending == LineEnding::CrLf {
+        line = line.replace('\n', "\r\n");
+    }
+    #[cfg(feature = "hmac")]
+    if let Some(chain) = &ctx.chain {
+        chain.seal(&mut line);
+    }
+    line
+}
+
+/// continue the macs of `Log2::hmac()` in the file just opened
+#[cfg(feature = "hmac")]
+fn resume(ctx: &Context) -> Result<(), std::io::Error> {
+    match &ctx.chain {
+        Some(chain) => chain.resume(&ctx.path),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "hmac"))]
+fn resume(_: &Context) -> Result<(), std::io::Error> {
+    Ok(())
+}
+
+/// warn when the free space fell below `Log2::low_disk()`
+#[cfg(feature = "disk")]
+fn space(ctx: &mut Context) {
+    let Some(disk) = ctx.disk.as_mut() else {
+        return;
+    };
+    if ctx.path.as_os_str().is_empty() {
+        return;
+    }
+    let Some(free) = disk.check(&ctx.path) else {
+        return;
+    };
+    let hook = disk.hook.clone();
+    let message = format!(
+        "low disk space, {free} bytes free for {}",
+        ctx.path.display()
+    );
+    ctx.report(Level::Warn, message);
+    if let Some(hook) = hook {
+        hook(free);
+    }
+}
+
+#[cfg(not(feature = "disk"))]
+fn space(_: &mut Context) {}
+
+/// `Log2::low_disk()` watches the free space
+#[cfg(all(feature = "tokio", feature = "disk"))]
+fn low_disk(ctx: &Context) -> bool {
+    ctx.disk.is_some()
+}
+
+#[cfg(all(feature = "tokio", not(feature = "disk")))]
+fn low_disk(_: &Context) -> bool {
+    false
+}
+
+/// apply `Log2::mode()` and `Log2::owner()` to the active file, which might
+/// exist already with other permissions
+#[cfg(unix)]
+fn protect(ctx: &Context) -> Result<(), std::io::Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(mode) = ctx.mode {
+        std::fs::set_permissions(&ctx.path, std::fs::Permissions::from_mode(mode))?;
+    }
+    if let Some((uid, gid)) = ctx.owner {
+        std::os::unix::fs::chown(&ctx.path, Some(uid), Some(gid))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn protect(_: &Context) -> Result<(), std::io::Error> {
+    Ok(())
+}
+
+/// follow the path of `log2::template()` to its current expansion, e.g. the
+/// directory of the new day
+fn retarget(ctx: &mut Context) -> Result<(), std::io::Error> {
+    let Some(template) = &ctx.template else {
+        return Ok(());
+    };
+    let path = naming::expand(template);
+    if path != ctx.path {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        ctx.path = path;
+    }
+    Ok(())
+}
+
+/// the report of a failed rename or removal of a rotation
+fn moved(a: &std::path::Path, b: Option<&std::path::Path>, e: std::io::Error) -> String {
+    match b {
+        Some(b) => format!("failed to rotate {} to {}: {e}", a.display(), b.display()),
+        None => format!("failed to remove {}: {e}", a.display()),
+    }
+}
+
+/// the file is over the size or `Log2::rotation_policy()` asks for a
+/// rotation, which `rotate()` then does below the size too
+fn due(ctx: &Context, size: u64) -> bool {
+    if size >= ctx.size {
+        return true;
+    }
+    let Some(policy) = &ctx.policy else {
+        return false;
+    };
+    let state = RotationState {
+        path: &ctx.path,
+        size,
+        records: ctx.records.load(Ordering::Relaxed),
+        opened: ctx.opened,
+        now: now(),
+    };
+    let due = policy.should_rotate(&state);
+    if due {
+        ctx.fresh.store(true, Ordering::Relaxed);
+    }
+    due
+}
+
+/// bytes counted towards the next rotation, after a failed rotation the file
+/// stays over the limit, retry once another full segment was written
+fn filled(ctx: &Context, len: u64) -> u64 {
+    if len >= ctx.size {
+        0
+    } else {
+        len
+    }
+}
+
+/// level names and brackets for stdout in the colors of the theme
+fn palette(theme: &Theme, color: bool, labels: &[String; 6]) -> ([String; 6], (String, String)) {
+    let theme = match color {
+        true => *theme,
+        false => Theme::plain(),
+    };
+    let levels = [
+        Style::default().paint(&labels[0]),
+        theme.error.paint(&labels[1]),
+        theme.warn.paint(&labels[2]),
+        theme.info.paint(&labels[3]),
+        theme.debug.paint(&labels[4]),
+        theme.trace.paint(&labels[5]),
+    ];
+    (levels, (theme.bracket.paint("["), theme.bracket.paint("]")))
+}
+
+/// local time, or the time in the zone of `Log2::timezone()`
+#[cfg(feature = "chrono")]
+fn timestamp(ctx: &Context, time: std::time::SystemTime) -> String {
+    let format = match ctx.precision {
+        TimePrecision::Millis => "%Y-%m-%d %H:%M:%S%.3f",
+        TimePrecision::Micros => "%Y-%m-%d %H:%M:%S%.6f",
+        TimePrecision::Nanos => "%Y-%m-%d %H:%M:%S%.9f",
+    };
+    #[cfg(feature = "timezone")]
+    if let Some(timezone) = &ctx.timezone {
+        return chrono::DateTime::<chrono::Utc>::from(time)
+            .with_timezone(timezone)
+            .format(format)
+            .to_string();
+    }
+    chrono::DateTime::<Local>::from(time)
+        .format(format)
+        .to_string()
+}
+
+/// milliseconds, or the unit of the precision, since the unix epoch
+#[cfg(not(feature = "chrono"))]
+fn timestamp(ctx: &Context, time: std::time::SystemTime) -> String {
+    let nanos = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    (nanos / ctx.precision.unit()).to_string()
+}
+
+/// timestamp of the last record, records in the same unit of the precision reuse it
+#[derive(Default)]
+struct Clock {
+    tick: u128,
+    text: String,
+    previous: Option<std::time::SystemTime>,
+}
+
+impl Clock {
+    /// time since the previous record, zero for the first one
+    fn delta(&mut self, time: std::time::SystemTime) -> std::time::Duration {
+        let previous = self.previous.replace(time).unwrap_or(time);
+        time.duration_since(previous).unwrap_or_default()
+    }
+
+    fn format(&mut self, ctx: &Context, time: std::time::SystemTime) -> &str {
+        let nanos = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let tick = nanos / ctx.precision.unit();
+        if tick != self.tick || self.text.is_empty() {
+            self.tick = tick;
+            self.text = timestamp(ctx, time);
+        }
+        &self.text
+    }
+}
+
+fn flush(tx: &Tx) {
+    let (done, wait) = std::sync::mpsc::channel();
+    let done = Box::new(move || {
+        let _ = done.send(());
+    });
+    if tx.send(Action::Flush(done)) {
+        // the sender is dropped if the worker exits, so this never hangs
+        let _ = tx.wait(|| wait.recv());
+    }
+}
+
+fn sync(file: &mut Target) -> Result<(), std::io::Error> {
+    file.flush()?;
+    file.get_mut().sync_data()
+}
+
+fn writer(ctx: &Context, file: fs::File) -> Target {
+    #[cfg(feature = "memory-fs")]
+    let file = match file {
+        fs::File::Disk(file) => file,
+        memory => return std::io::BufWriter::with_capacity(ctx.buffer, Sink::Memory(memory)),
+    };
+    #[cfg(not(feature = "memory-fs"))]
+    let fs::File::Disk(file) = file;
+    #[cfg(feature = "mmap")]
+    if ctx.mmap && !ctx.shared {
+        match mapped::Mapped::open(&ctx.path) {
+            Ok(mapped) => {
+                return std::io::BufWriter::with_capacity(
+                    ctx.buffer,
+                    Sink::Mapped(Box::new(mapped)),
+                )
+            }
+            Err(e) => ctx.report(
+                Level::Warn,
+                format!("mmap unavailable, writing directly: {e}"),
+            ),
+        }
+    }
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    let file = match ctx.io_uring {
+        true => match uring::Uring::new(file) {
+            Ok(uring) => {
+                return std::io::BufWriter::with_capacity(ctx.buffer, Sink::Uring(Box::new(uring)))
+            }
+            Err(file) => {
+                ctx.report(Level::Warn, "io_uring unavailable, writing directly".into());
+                file
+            }
+        },
+        false => file,
+    };
+    std::io::BufWriter::with_capacity(ctx.buffer, Sink::File(file, ctx.retry))
+}
+
+/// how long the worker waits for the queue, records in the ring don't wake it up
+fn poll_timeout(ctx: &Context) -> std::time::Duration {
+    if ctx.ring.is_some() {
+        return std::time::Duration::from_millis(1);
+    }
+    if ctx.interval.is_zero() {
+        std::time::Duration::from_secs(1)
+    } else {
+        ctx.interval.min(std::time::Duration::from_secs(1))
+    }
+}
+
+/// owned copy of a record in the ring, through the layers, none when a layer
+/// dropped it
+fn ring_entry(ctx: &Context, frame: &ring::Frame) -> Option<Entry> {
+    let mut message = ctx.pool.take();
+    message.push_str(&String::from_utf8_lossy(&frame.buf[..frame.len]));
+    let mut view = RecordView {
+        level: frame.level,
+        module: frame.module().to_string().into(),
+        message,
+    };
+    for layer in &ctx.layers {
+        match layer.layer(view) {
+            Some(next) => view = next,
+            None => {
+                ctx.counters.handled();
+                return None;
+            }
+        }
+    }
+    let RecordView {
+        level,
+        module,
+        mut message,
+    } = view;
+    ctx.scrub.apply(&mut message);
+    Some(Entry {
+        sequence: frame.sequence,
+        thread: frame.thread.clone(),
+        level,
+        module,
+        time: frame.time,
+        message,
+        raw: false,
+    })
+}
+
+/// maximum number of queued actions handled in one batch
+const BATCH: usize = 1024;
+
+/// write the lines with as few vectored writes as possible
+fn write_lines(
+    file: &mut Target,
+    lines: &mut Vec<String>,
+    ctx: &Context,
+) -> Result<(), std::io::Error> {
+    let mut slices: Vec<_> = lines
+        .iter()
+        .map(|line| std::io::IoSlice::new(line.as_bytes()))
+        .collect();
+    let mut bufs = &mut slices[..];
+    while !bufs.is_empty() {
+        match file.write_vectored(bufs) {
+            Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+            Ok(n) => std::io::IoSlice::advance_slices(&mut bufs, n),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    for line in lines.drain(..) {
+        ctx.counters.written(line.len() as u64);
+        ctx.pool.give(line);
+    }
+    Ok(())
+}
+
+/// render a record for stdout and for the file, the message goes back to the pool
+fn render(
+    ctx: &Context,
+    clock: &mut Clock,
+    entry: Entry,
+    file: bool,
+) -> (Option<String>, Option<String>) {
+    ctx.counters.handled();
+    if entry.raw {
+        return (None, raw(ctx, entry.message, file));
+    }
+    let elapsed = entry.time.duration_since(ctx.start).unwrap_or_default();
+    let delta = clock.delta(entry.time);
+    let time = clock.format(ctx, entry.time);
+    let mut origin = String::new();
+    if ctx.module {
+        origin = format!("[{}] ", entry.module);
+    }
+
+    // optional columns between the time and the level
+    let mut columns = String::new();
+    if ctx.sequence {
+        let _ = fmt::Write::write_fmt(&mut columns, format_args!("[#{}] ", entry.sequence));
+    }
+    if let Some(hostname) = &ctx.hostname {
+        let _ = fmt::Write::write_fmt(&mut columns, format_args!("[{hostname}] "));
+    }
+    if let Some(pid) = ctx.pid {
+        let _ = fmt::Write::write_fmt(&mut columns, format_args!("[{pid}] "));
+    }
+    if let Some(thread) = &entry.thread {
+        let _ = fmt::Write::write_fmt(
+            &mut columns,
+            format_args!("[{}:{}] ", thread.name, thread.id),
+        );
+    }
+    if ctx.elapsed {
+        let _ = fmt::Write::write_fmt(
+            &mut columns,
+            format_args!("[{:.6}] ", elapsed.as_secs_f64()),
+        );
+    }
+    if ctx.delta {
+        let _ = fmt::Write::write_fmt(&mut columns, format_args!("[+{:.6}] ", delta.as_secs_f64()));
+    }
+
+    let parts = Parts {
+        sequence: entry.sequence,
+        hostname: ctx.hostname.as_deref().unwrap_or_default(),
+        pid: ctx.pid.unwrap_or_default(),
+        thread_id: entry.thread.as_ref().map(|t| t.id).unwrap_or_default(),
+        thread_name: entry.thread.as_ref().map(|t| &*t.name).unwrap_or_default(),
+        time,
+        elapsed,
+        delta,
+        level: entry.level,
+        label: &ctx.labels[entry.level as usize],
+        module: &entry.module,
+        message: &entry.message,
+    };
+
+    // stdout
+    let mut tee = None;
+    if ctx.tee && entry.level <= ctx.tee_level {
+        let message = escape(ctx, &entry.message, ctx.tee_escape);
+        let parts = Parts {
+            message: &message,
+            ..parts
+        };
+        tee = Some(match &ctx.tee_format {
+            Some(format) => {
+                let mut buf = String::new();
+                format.format(&mut buf, &parts);
+                buf
+            }
+            None => {
+                let level = &ctx.levels[entry.level as usize];
+                let (open, close) = &ctx.brackets;
+                let time = match ctx.time == Style::default() {
+                    true => std::borrow::Cow::Borrowed(time),
+                    false => std::borrow::Cow::Owned(ctx.time.paint(time)),
+                };
+                let pad = ctx.pads[entry.level as usize];
+                format!(
+                    "{open}{time}{close} {columns}{open}{level}{close}{:pad$} {origin}{message}",
+                    ""
+                )
+            }
+        });
+    }
+    if let Some(tee) = &mut tee {
+        continued(ctx, tee, &entry.message);
+    }
+
+    // file
+    let mut line = None;
+    let file = file
+        && entry.level <= ctx.file_level
+        && ctx
+            .quota
+            .as_ref()
+            .is_none_or(|q| q.allows(entry.level, entry.time));
+    let recent = ctx
+        .recent
+        .as_ref()
+        .filter(|_| entry.level <= ctx.file_level);
+    if file || recent.is_some() {
+        let message = escape(ctx, &entry.message, ctx.escape);
+        let parts = Parts {
+            message: &message,
+            ..parts
+        };
+        let mut buf = ctx.pool.take();
+        match &ctx.file_format {
+            Some(format) => {
+                format.format(&mut buf, &parts);
+                buf.push('\n');
+            }
+            None => {
+                let _ = fmt::Write::write_fmt(
+                    &mut buf,
+                    format_args!(
+                        "[{time}] {columns}[{}]{:pad$} {origin}{}\n",
+                        ctx.labels[entry.level as usize],
+                        "",
+                        message,
+                        pad = ctx.pads[entry.level as usize],
+                    ),
+                );
+            }
+        }
+        if ctx.strip_ansi {
+            strip_ansi(&mut buf);
+        }
+        continued(ctx, &mut buf, &entry.message);
+        if ctx.line_ending == LineEnding::CrLf {
+            buf = buf.replace('\n', "\r\n");
+        }
+        if let Some(recent) = recent {
+            recent.push(&buf);
+        }
+        match file {
+            true => {
+                #[cfg(feature = "hmac")]
+                if let Some(chain) = &ctx.chain {
+                    chain.seal(&mut buf);
+                }
+                if let Some(quota) = ctx.quota.as_ref().filter(|q| q.spend(buf.len() as u64)) {
+                    ctx.report(Level::Warn, quota::used_up(quota.limit));
+                }
+                ctx.records.fetch_add(1, Ordering::Relaxed);
+                line = Some(buf);
+            }
+            false => ctx.pool.give(buf),
+        }
+    }
+
+    ctx.pool.give(entry.message);
+    (tee, line)
+}
+
+/// a line of `Handle::write_line()` for the file, only the line ending and the
+/// mac of `Log2::hmac()` are added
+fn raw(ctx: &Context, mut line: String, file: bool) -> Option<String> {
+    if !file {
+        ctx.pool.give(line);
+        return None;
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    line.push_str(match ctx.line_ending {
+        LineEnding::Lf => "\n",
+        LineEnding::CrLf => "\r\n",
+    });
+    #[cfg(feature = "hmac")]
+    if let Some(chain) = &ctx.chain {
+        chain.seal(&mut line);
+    }
+    ctx.records.fetch_add(1, Ordering::Relaxed);
+    Some(line)
+}
+
+/// the redactions and the length limit of the messages, after the layers
+#[derive(Clone, Default)]
+struct Scrub {
+    #[cfg(feature = "redact")]
+    redactions: Vec<(regex::Regex, String)>,
+    max_message_len: usize,
+}
+
+impl Scrub {
+    fn apply(&self, message: &mut String) {
+        #[cfg(feature = "redact")]
+        for (regex, replacement) in &self.redactions {
+            if let std::borrow::Cow::Owned(redacted) = regex.replace_all(message, replacement) {
+                *message = redacted;
+            }
+        }
+        if self.max_message_len > 0 {
+            truncate(message, self.max_message_len);
+        }
+    }
+}
+
+/// cut a message to `len` bytes on a char boundary, with a marker of the cut bytes
+fn truncate(message: &mut String, len: usize) {
+    if message.len() <= len {
+        return;
+    }
+    let mut end = len;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = message.len() - end;
+    message.truncate(end);
+    let _ = fmt::Write::write_fmt(message, format_args!("…[truncated {cut} bytes]"));
+}
+
+/// control characters of a message as escapes, newlines stay when the
+/// continuation lines are indented or prefixed anyway
+fn escape<'a>(ctx: &Context, message: &'a str, on: bool) -> std::borrow::Cow<'a, str> {
+    let newlines = ctx.continuation != Continuation::Off;
+    let plain = |c: char| !c.is_control() || c == '\t' || (c == '\n' && newlines);
+    if !on || message.chars().all(plain) {
+        return std::borrow::Cow::Borrowed(message);
+    }
+    let mut text = String::with_capacity(message.len() + 8);
+    for c in message.chars() {
+        match c {
+            c if plain(c) => text.push(c),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            c => {
+                let _ = fmt::Write::write_fmt(&mut text, format_args!("\\x{:02x}", c as u32));
+            }
+        }
+    }
+    std::borrow::Cow::Owned(text)
+}
+
+/// indent or prefix the continuation lines of a multi-line message, the
+/// first line of the message is expected at the end of the first line
+fn continued(ctx: &Context, line: &mut String, message: &str) {
+    if ctx.continuation == Continuation::Off || !message.contains('\n') {
+        return;
+    }
+    let end = line.len() - line.ends_with('\n') as usize;
+    let Some(first) = line[..end].find('\n') else {
+        return;
+    };
+    let prefix = match &ctx.continuation {
+        Continuation::Prefix(prefix) => prefix.clone(),
+        _ => {
+            let lead = message.split('\n').next().unwrap_or_default();
+            " ".repeat(width(&line[..first]).saturating_sub(width(lead)))
+        }
+    };
+    let mut text = String::with_capacity(line.len() + prefix.len() * 4);
+    for (i, part) in line[..end].split('\n').enumerate() {
+        if i > 0 {
+            text.push('\n');
+            text.push_str(&prefix);
+        }
+        text.push_str(part);
+    }
+    text.push_str(&line[end..]);
+    *line = text;
+}
+
+/// remove the ANSI escapes of a line in place
+fn strip_ansi(line: &mut String) {
+    if !line.contains('\x1b') {
+        return;
+    }
+    let mut escape = false;
+    line.retain(|c| match escape {
+        // up to the final byte of the escape
+        true => {
+            escape = !c.is_ascii_alphabetic();
+            false
+        }
+        false => {
+            escape = c == '\x1b';
+            !escape
+        }
+    });
+}
+
+/// the columns a text takes on a terminal, ANSI escapes take none
+fn width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip up to the final byte of the escape
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            continue;
+        }
+        width += 1;
+    }
+    width
+}
+
+/// the file of `Log2::sync()`, written on the caller thread
+struct Direct {
+    ctx: Context,
+    clock: Clock,
+    target: Option<Target>,
+    size: u64,
+}
+
+impl Direct {
+    fn open(ctx: Context) -> Result<Self, std::io::Error> {
+        let mut direct = Direct {
+            ctx,
+            clock: Clock::default(),
+            target: None,
+            size: 0,
+        };
+        if !direct.ctx.path.as_os_str().is_empty() {
+            direct.reopen()?;
+        }
+        Ok(direct)
+    }
+
+    fn reopen(&mut self) -> Result<(), std::io::Error> {
+        let _lock = lock(&self.ctx)?;
+        let file = rotate(&mut self.ctx)?;
+        self.size = filled(&self.ctx, file.len()?);
+        self.target = Some(writer(&self.ctx, file));
+        Ok(())
+    }
+
+    fn write(&mut self, entry: Entry) -> Result<(), std::io::Error> {
+        let ctx = &self.ctx;
+        let level = entry.level;
+        let (tee, line) = render(ctx, &mut self.clock, entry, self.target.is_some());
+        if let Some(tee) = tee {
+            print(level, &tee);
+        }
+        let (Some(file), Some(line)) = (self.target.as_mut(), line) else {
+            return Ok(());
+        };
+        if ctx.shared {
+            self.size = append(&mut self.ctx, &mut self.target, &mut vec![line])?;
+            return Ok(());
+        }
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        if ctx.sync == SyncPolicy::EveryWrite {
+            sync(file)?;
+        }
+        self.size += line.len() as u64;
+        ctx.counters.written(line.len() as u64);
+        ctx.pool.give(line);
+        if due(ctx, self.size) {
+            if ctx.sync != SyncPolicy::Never {
+                sync(file)?;
+            }
+            self.reopen()?;
+        }
+        Ok(())
+    }
+
+    /// a diagnostic about log2 itself as a record, on stderr when even that
+    /// can't be written
+    fn report(&mut self, level: Level, message: String) {
+        let entry = Entry {
+            sequence: next_sequence(),
+            thread: None,
+            level,
+            module: std::borrow::Cow::Borrowed(INTERNAL),
+            time: now(),
+            message,
+            raw: false,
+        };
+        self.ctx.counters.record(level);
+        self.ctx.counters.queued();
+        if let Err(e) = self.write(entry) {
+            self.ctx.counters.failed();
+            eprintln!("log2: {e}");
+        }
+    }
+
+    /// bytes of a `RotatingFile`, rotated at the end of a line once over the size
+    fn append(&mut self, buf: &[u8]) -> Result<(), std::io::Error> {
+        let Some(file) = self.target.as_mut() else {
+            return Ok(());
+        };
+        file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        self.ctx.counters.written(buf.len() as u64);
+        if buf.ends_with(b"\n") && due(&self.ctx, self.size) {
+            file.flush()?;
+            if self.ctx.sync != SyncPolicy::Never {
+                sync(file)?;
+            }
+            self.reopen()?;
+        }
+        Ok(())
+    }
+
+    fn redirect(&mut self, path: std::path::PathBuf) -> Result<(), std::io::Error> {
+        if let Some(file) = self.target.as_mut() {
+            file.flush()?;
+        }
+        self.ctx.path = path;
+        self.ctx.template = None;
+        self.reopen()
+    }
+}
+
+fn worker(rx: Receiver<Action>, mut ctx: Context) -> Result<(), std::io::Error> {
+    let mut target: Option<Target> = None;
+    let mut size: u64 = 0;
+    let mut last = size;
+
+    if !ctx.path.as_os_str().is_empty() {
+        let _lock = lock(&ctx)?;
+        let file = rotate(&mut ctx)?;
+        size = filled(&ctx, file.len()?);
+        last = size;
+        target = Some(writer(&ctx, file));
+    }
+
+    // wake up at least every second, or as often as the flush interval demands
+    let timeout = poll_timeout(&ctx);
+    let mut ts = std::time::Instant::now();
+    let mut synced = ts;
+    let mut dirty = false;
+
+    // stdout on its own thread, so a slow terminal never holds up the file
+    let mut shard = None;
+    if ctx.tee && ctx.sharded {
+        let (tx, rx) = channel::<String>();
+        let thread = std::thread::Builder::new()
+            .name(WORKER.into())
+            .spawn(move || {
+                for line in rx.iter() {
+                    println!("{line}");
+                }
+            })?;
+        shard = Some((tx, thread));
+    }
+
+    let mut clock = Clock::default();
+    let mut recorder = (ctx.recorder > 0).then(|| recorder::Recorder::new(ctx.recorder));
+    let mut actions = Vec::with_capacity(BATCH);
+    let mut lines = Vec::with_capacity(BATCH);
+
+    loop {
+        // drain everything queued so far and handle it as one batch
+        if let Ok(action) = rx.recv_timeout(timeout) {
+            actions.push(action);
+            actions.extend(rx.try_iter().take(BATCH - 1));
+        }
+        // records in the ring were logged before the actions just received
+        if let Some(ring) = &ctx.ring {
+            let received = actions.len();
+            while let Some(entry) = ring.pop(|frame| ring_entry(&ctx, frame)) {
+                actions.extend(entry.map(Action::Record));
+            }
+            actions.rotate_left(received);
+        }
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.replay(&ctx, &mut actions);
+        }
+        let mut exit = false;
+        for action in actions.drain(..) {
+            // keep the order of pending lines and the other actions
+            if !matches!(action, Action::Record(_)) && !lines.is_empty() {
+                if ctx.shared {
+                    size = append(&mut ctx, &mut target, &mut lines)?;
+                    last = size;
+                    dirty = true;
+                } else if let Some(file) = target.as_mut() {
+                    write_lines(file, &mut lines, &ctx)?;
+                    dirty = true;
+                }
+            }
+
+            match action {
+                Action::Record(entry) => {
+                    let (tee, line) = render(&ctx, &mut clock, entry, target.is_some());
+                    if let Some(tee) = tee {
+                        match &shard {
+                            Some((tx, _)) => {
+                                if tx.send(tee).is_err() {
+                                    ctx.report(Level::Error, "stdout thread is gone".into());
+                                }
+                            }
+                            None => println!("{tee}"),
+                        }
+                    }
+                    let Some(line) = line else {
+                        continue;
+                    };
+                    size += line.len() as u64;
+                    lines.push(line);
+                    let due = due(&ctx, size);
+                    if due && ctx.shared {
+                        size = append(&mut ctx, &mut target, &mut lines)?;
+                        last = size;
+                        dirty = true;
+                    } else if due {
+                        let file = target.as_mut().unwrap();
+                        write_lines(file, &mut lines, &ctx)?;
+                        file.flush()?;
+                        if ctx.sync != SyncPolicy::Never {
+                            sync(file)?;
+                            dirty = false;
+                        }
+                        let f = rotate(&mut ctx)?;
+                        size = filled(&ctx, f.len()?);
+                        last = size;
+                        target = Some(writer(&ctx, f));
+                    }
+                }
+                Action::Flush(done) => {
+                    if let Some(file) = target.as_mut() {
+                        file.flush()?;
+                        if dirty && ctx.sync != SyncPolicy::Never {
+                            sync(file)?;
+                            dirty = false;
+                        }
+                    }
+                    done();
+                }
+                Action::Exit => {
+                    if let Some(file) = target.as_mut() {
+                        file.flush()?;
+                        if dirty && ctx.sync != SyncPolicy::Never {
+                            sync(file)?;
+                        }
+                    }
+                    exit = true;
+                    break;
+                }
+                Action::Redirect(path) => {
+                    if let Some(file) = target.as_mut() {
+                        file.flush()?;
+                        if dirty && ctx.sync != SyncPolicy::Never {
+                            sync(file)?;
+                            dirty = false;
+                        }
+                    }
+                    ctx.path = path;
+                    ctx.template = None;
+                    let _lock = lock(&ctx)?;
+                    let file = rotate(&mut ctx)?;
+                    size = filled(&ctx, file.len()?);
+                    last = size;
+                    target = Some(writer(&ctx, file));
+                }
+                Action::Tee(tee) => ctx.tee = tee,
+                Action::Format(format) => {
+                    ctx.tee_format = Some(format.clone());
+                    ctx.file_format = Some(format);
+                }
+                Action::Rotation(size, count) => {
+                    ctx.size = size;
+                    ctx.count = count;
+                }
+                // taken by the flight recorder, or no recorder to dump
+                Action::Remember(_) | Action::Dump => {}
+            }
+        }
+        if exit {
+            break;
+        }
+        if ctx.shared && !lines.is_empty() {
+            size = append(&mut ctx, &mut target, &mut lines)?;
+            last = size;
+            dirty = true;
+        }
+        if let Some(file) = target.as_mut() {
+            if !lines.is_empty() {
+                write_lines(file, &mut lines, &ctx)?;
+                dirty = true;
+            }
+            if dirty && ctx.sync == SyncPolicy::EveryWrite {
+                sync(file)?;
+                dirty = false;
+            }
+        }
+        // flush every interval, zero flushes after every write
+        if size > last {
+            if let Some(file) = target.as_mut() {
+                if ts.elapsed() >= ctx.interval {
+                    ts = std::time::Instant::now();
+                    file.flush()?;
+                    last = size;
+                }
+            }
+        }
+        // sync every second
+        if dirty && ctx.sync == SyncPolicy::EverySecond && synced.elapsed().as_secs() >= 1 {
+            if let Some(file) = target.as_mut() {
+                sync(file)?;
+            }
+            synced = std::time::Instant::now();
+            dirty = false;
+        }
+        space(&mut ctx);
+    }
+
+    // let the stdout thread finish the remaining lines
+    if let Some((tx, thread)) = shard {
+        drop(tx);
+        let _ = thread.join();
+    }
+
+    Ok(())
+}
+
+/// start the log2 instance by default, to stdout unless `LOG2_*` says otherwise
+pub fn start() -> Handle {
+    stdout().start()
+}
+
+/// create a log2 instance to stdout
+pub fn stdout() -> Log2 {
+    let mut logger = Log2::new();
+    logger.tee = true;
+    logger
+}
+
+/// capture records in memory for tests, inspect them with `captured()`, add
+/// `tee(true)` to print them as well
+pub fn capture() -> Log2 {
+    let mut logger = Log2::new();
+    logger.capture = true;
+    logger
+}
+
+/// create the directory and the file
+fn create(path: &std::path::Path) -> Result<(), std::io::Error> {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+
+    std::fs::OpenOptions::new()
         .create(true)
         .append(true)
-        .open(path)
-        .expect("error to open file");
+        .open(path)?;
+    Ok(())
+}
+
+/// log to a file of this process only, `app.log` becomes `app.4321.log`
+/// with the process id, so instances of one binary never share a file
+pub fn open_unique(path: impl AsRef<std::path::Path>) -> Log2 {
+    open(naming::unique(path.as_ref()))
+}
+
+/// log to a file named by a template like `logs/%Y-%m-%d/{hostname}-{pid}.log`,
+/// expanded again whenever the file is rotated
+pub fn template(template: &str) -> Log2 {
+    let path = naming::expand(template);
+    let mut logger = open(path);
+    logger.template = Some(template.into());
+    logger
+}
 
+/// log to file
+pub fn open(path: impl AsRef<std::path::Path>) -> Log2 {
+    // the file is created by `start()`, which might fall back
     let mut logger = Log2::new();
-    logger.path = path.into();
+    logger.path = path.as_ref().into();
     logger
 }
 
-fn start_log2(mut logger: Log2) -> Handle {
-    let rx = logger.rx.take().unwrap();
+const WORKER: &str = "log2";
+
+fn capture_panics() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(capture_panics_hook);
+}
+
+/// once per process, the streams stay redirected for the next `start()`
+#[cfg(unix)]
+fn capture_std_streams() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        if let Err(e) = streams::capture() {
+            log::warn!(target: INTERNAL, "can't capture stdout and stderr: {e}");
+        }
+    });
+}
+
+fn capture_panics_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        // the worker can't wait for itself to flush
+        if name != WORKER {
+            let backtrace = std::backtrace::Backtrace::force_capture();
+            log::error!(target: "panic", "thread '{name}' {info}\n{backtrace}");
+            log::logger().flush();
+        }
+        previous(info);
+    }));
+}
 
-    let ctx = Context {
-        rx,
+fn context(logger: &Log2) -> Context {
+    // the level of a sink without its own, the global level once the other
+    // sink lowered the threshold, otherwise the callers filter by `set_level()`
+    let split = logger.tee_level.is_some() || logger.file_level.is_some();
+    let sink_level = match split {
+        true => logger.level.unwrap_or(LevelFilter::Trace),
+        false => LevelFilter::Trace,
+    };
+    let color = logger.color.enabled();
+    let (levels, brackets) = palette(&logger.theme, color, &logger.labels);
+    let width = match logger.align {
+        true => logger.labels[1..].iter().map(|l| l.chars().count()).max(),
+        false => None,
+    };
+    let pads = std::array::from_fn(|i| {
+        width.map_or(0, |width| {
+            width - logger.labels[i].chars().count().min(width)
+        })
+    });
+    Context {
         path: logger.path.clone(),
         size: logger.filesize,
         count: logger.count,
-    };
+        naming: logger.naming,
+        rotation_name: logger.rotation_name.clone(),
+        fs: logger.fs.clone(),
+        policy: logger.policy.clone(),
+        template: logger.template.clone(),
+        fresh: AtomicBool::new(logger.rotate_on_open),
+        shared: logger.shared,
+        latest: logger.latest.clone(),
+        compressor: compressor(logger),
+        on_prune: logger.on_prune.clone(),
+        #[cfg(feature = "disk")]
+        disk: logger
+            .low_disk
+            .map(|threshold| disk::Disk::new(threshold, logger.on_low_disk.clone())),
+        #[cfg(feature = "hmac")]
+        chain: logger.hmac.as_deref().map(seal::Chain::new),
+        #[cfg(unix)]
+        inode: (0, 0),
+        interval: logger.flush_interval,
+        sync: logger.sync_policy,
+        retry: logger.retry,
+        buffer: logger.buffer,
+        sharded: logger.sharded,
+        #[cfg(all(feature = "io-uring", target_os = "linux"))]
+        io_uring: logger.io_uring,
+        #[cfg(feature = "mmap")]
+        mmap: logger.mmap,
+        #[cfg(unix)]
+        tee: logger.tee && !logger.std_streams,
+        #[cfg(not(unix))]
+        tee: logger.tee,
+        tee_level: logger.tee_level.unwrap_or(sink_level),
+        file_level: logger.file_level.unwrap_or(sink_level),
+        quota: logger.daily_quota.map(quota::Quota::new),
+        module: logger.module,
+        levels,
+        labels: logger.labels.clone(),
+        pads,
+        continuation: logger.continuation.clone(),
+        escape: logger.escape,
+        tee_escape: logger.tee_escape,
+        strip_ansi: logger.strip_ansi,
+        line_ending: logger.line_ending,
+        header: logger.header.clone(),
+        footer: logger.footer.clone(),
+        records: AtomicU64::new(0),
+        opened: now(),
+        #[cfg(unix)]
+        mode: logger.mode,
+        #[cfg(unix)]
+        owner: logger.owner,
+        brackets,
+        time: match color {
+            true => logger.theme.time,
+            false => Style::default(),
+        },
+        pool: logger.pool.clone(),
+        ring: logger.ring.clone(),
+        recorder: logger.recorder,
+        recent: logger.recent.clone(),
+        tee_format: logger.tee_format.clone(),
+        file_format: logger.file_format.clone(),
+        layers: Vec::new(),
+        scrub: logger.scrub.clone(),
+        #[cfg(feature = "timezone")]
+        timezone: logger.timezone,
+        precision: logger.precision,
+        sequence: logger.sequence,
+        hostname: logger.hostname.then(hostname),
+        pid: logger.pid.then(std::process::id),
+        elapsed: logger.elapsed,
+        delta: logger.delta,
+        start: now(),
+        counters: logger.counters.clone(),
+        tx: logger.tx.clone(),
+    }
+}
+
+/// the background thread of `Log2::compress()`, `Log2::on_rotate()` and the like
+fn compressor(logger: &Log2) -> Option<compress::Compressor> {
+    #[cfg(any(feature = "gzip", feature = "lz4"))]
+    let codec = logger.compress.then_some(logger.codec);
+    #[cfg(not(any(feature = "gzip", feature = "lz4")))]
+    let codec = None;
+    let compressor = compress::Compressor::new(codec, logger.tx.clone(), logger.counters.clone());
+    #[cfg(feature = "encrypt")]
+    let compressor = compressor.encrypt(logger.encrypt);
+    #[cfg(feature = "bundle")]
+    let compressor = compressor.bundle(logger.bundle);
+    let compressor = compressor.notify(logger.on_rotate.clone());
+    (!compressor.idle()).then_some(compressor)
+}
+
+/// the logger installed into `log`, which takes only one per process, it passes
+/// records on to the pipeline of the latest `start()`
+struct Dispatcher;
+
+static DISPATCHER: Dispatcher = Dispatcher;
+static ACTIVE: std::sync::RwLock<Option<std::sync::Arc<Log2>>> = std::sync::RwLock::new(None);
+
+impl log::Log for Dispatcher {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        match ACTIVE.read() {
+            Ok(active) => active.as_ref().is_some_and(|l| l.enabled(metadata)),
+            Err(_) => false,
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(active) = ACTIVE.read() {
+            if let Some(logger) = active.as_ref() {
+                logger.log(record);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        // flushing waits for the worker, don't hold the lock meanwhile
+        let logger = ACTIVE.read().ok().and_then(|active| active.clone());
+        if let Some(logger) = logger {
+            logger.flush();
+        }
+    }
+}
+
+fn install(logger: Log2) {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        log::set_logger(&DISPATCHER).expect("error to initialize log2");
+    });
 
-    let mut handle = Handle {
+    let panics = logger.capture_panics;
+    #[cfg(unix)]
+    let streams = logger.std_streams;
+    RECORDING.store(logger.recorder > 0, Ordering::Relaxed);
+    LEVEL.store(LevelFilter::Trace as usize, Ordering::Relaxed);
+    // the previous pipeline stops once its handle is dropped
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(std::sync::Arc::new(logger));
+    }
+    log::set_max_level(LevelFilter::Trace);
+
+    if panics {
+        capture_panics();
+    }
+    #[cfg(unix)]
+    if streams {
+        capture_std_streams();
+    }
+    install_exit_flush();
+}
+
+/// stdout, or the console of the browser
+fn print(level: Level, line: &str) {
+    #[cfg(all(feature = "console", target_arch = "wasm32"))]
+    console::write(level, line);
+    #[cfg(not(all(feature = "console", target_arch = "wasm32")))]
+    {
+        let _ = level;
+        println!("{line}");
+    }
+}
+
+fn start_log2(mut logger: Log2) -> Handle {
+    let rx = logger.rx.take().unwrap();
+    let mut ctx = context(&logger);
+    if logger.ring.is_some() {
+        ctx.layers = std::mem::take(&mut logger.layers);
+    }
+
+    // no threads on wasm
+    if logger.sync || cfg!(target_arch = "wasm32") {
+        drop(rx);
+        let direct = Direct::open(ctx).expect("error to open file");
+        logger.direct = Some(std::sync::Arc::new(std::sync::Mutex::new(direct)));
+        let handle = Handle {
+            tx: logger.tx.clone(),
+            thread: None,
+            #[cfg(feature = "tokio")]
+            task: None,
+            path: logger.path.clone(),
+            count: logger.count,
+            naming: logger.naming,
+            rotation_name: logger.rotation_name.clone(),
+            fs: logger.fs.clone(),
+            template: logger.template.clone(),
+            counters: logger.counters.clone(),
+            recent: logger.recent.clone(),
+            direct: logger.direct.clone(),
+            #[cfg(feature = "watch")]
+            modules: logger.modules.clone(),
+            #[cfg(feature = "watch")]
+            watcher: None,
+        };
+        install(logger);
+        return handle;
+    }
+
+    let thread = std::thread::Builder::new()
+        .name(WORKER.into())
+        .spawn(move || {
+            let counters = ctx.counters.clone();
+            // the pipeline is gone with the worker
+            if let Err(e) = worker(rx, ctx) {
+                counters.failed();
+                eprintln!("log2: the worker stopped: {e}");
+            }
+        })
+        .expect("error to spawn log2 worker");
+
+    let handle = Handle {
         tx: logger.tx.clone(),
-        thread: None,
+        thread: Some(thread),
+        #[cfg(feature = "tokio")]
+        task: None,
+        path: logger.path.clone(),
+        count: logger.count,
+        naming: logger.naming,
+        rotation_name: logger.rotation_name.clone(),
+        fs: logger.fs.clone(),
+        template: logger.template.clone(),
+        counters: logger.counters.clone(),
+        recent: logger.recent.clone(),
+        direct: None,
+        #[cfg(feature = "watch")]
+        modules: logger.modules.clone(),
+        #[cfg(feature = "watch")]
+        watcher: None,
     };
 
-    let thread = std::thread::spawn(move || {
-        if let Err(message) = worker(ctx) {
-            println!("error: {message}");
+    install(logger);
+    handle
+}
+
+#[cfg(feature = "tokio")]
+fn start_task(mut logger: Log2) -> Handle {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    logger.tx = Tx::Task(tx);
+    logger.rx = None;
+    let mut ctx = context(&logger);
+    if logger.ring.is_some() {
+        ctx.layers = std::mem::take(&mut logger.layers);
+    }
+
+    let task = tokio::spawn(async move {
+        let counters = ctx.counters.clone();
+        // the pipeline is gone with the task
+        if let Err(e) = task::worker(rx, ctx).await {
+            counters.failed();
+            eprintln!("log2: the worker stopped: {e}");
         }
     });
 
-    handle.thread = Some(thread);
-
-    log::set_boxed_logger(Box::new(logger)).expect("error to initialize log2");
-    log::set_max_level(LevelFilter::Trace);
+    let handle = Handle {
+        tx: logger.tx.clone(),
+        thread: None,
+        task: Some(task),
+        path: logger.path.clone(),
+        count: logger.count,
+        naming: logger.naming,
+        rotation_name: logger.rotation_name.clone(),
+        fs: logger.fs.clone(),
+        template: logger.template.clone(),
+        counters: logger.counters.clone(),
+        recent: logger.recent.clone(),
+        direct: None,
+        #[cfg(feature = "watch")]
+        modules: logger.modules.clone(),
+        #[cfg(feature = "watch")]
+        watcher: None,
+    };
 
-    return handle;
+    install(logger);
+    handle
 }
@@ -0,0 +1,125 @@
+//! memory-mapped appender, the file is reserved in chunks and records are
+//! copied into the mapping, the file is trimmed to the written size on close
+//! and on rotation, and the padding a crash left behind is cut on open
+
+use std::io::{Read, Seek, Write};
+
+/// size of each reservation
+const CHUNK: u64 = 4 * 1024 * 1024;
+/// mapping offsets must be aligned to the allocation granularity, 64K covers windows
+const ALIGN: u64 = 64 * 1024;
+
+pub(crate) struct Mapped {
+    file: std::fs::File,
+    map: Option<memmap2::MmapMut>,
+    /// file offset of the mapping
+    base: u64,
+    /// bytes written to the file
+    len: u64,
+}
+
+impl Mapped {
+    pub(crate) fn open(path: &std::path::Path) -> Result<Self, std::io::Error> {
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+        let len = records(&mut file)?;
+        file.set_len(len)?;
+        Ok(Self {
+            file,
+            map: None,
+            base: 0,
+            len,
+        })
+    }
+
+    /// make sure the mapping has room for n more bytes
+    fn reserve(&mut self, n: u64) -> Result<(), std::io::Error> {
+        if let Some(map) = &self.map {
+            if self.len + n <= self.base + map.len() as u64 {
+                return Ok(());
+            }
+        }
+        if let Some(map) = self.map.take() {
+            map.flush_async()?;
+        }
+
+        self.base = self.len / ALIGN * ALIGN;
+        let size = (self.len - self.base + n).max(CHUNK).div_ceil(ALIGN) * ALIGN;
+        self.file.set_len(self.base + size)?;
+        let map = unsafe {
+            memmap2::MmapOptions::new()
+                .offset(self.base)
+                .len(size as usize)
+                .map_mut(&self.file)?
+        };
+        self.map = Some(map);
+        Ok(())
+    }
+
+    pub(crate) fn sync_data(&mut self) -> Result<(), std::io::Error> {
+        match &self.map {
+            Some(map) => map.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// cut the zeros of a reservation, left behind by a crash or by the mapping
+/// of the file being rotated, before its size counts towards a rotation
+pub(crate) fn trim(path: &std::path::Path) -> Result<(), std::io::Error> {
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    let len = records(&mut file)?;
+    file.set_len(len)
+}
+
+/// the length of a file without the zeros of a reservation, the records
+/// end with a line ending, never with a zero
+fn records(file: &mut std::fs::File) -> Result<u64, std::io::Error> {
+    let mut len = file.metadata()?.len();
+    let mut block = vec![0; ALIGN as usize];
+    while len > 0 {
+        let start = len.saturating_sub(ALIGN);
+        let block = &mut block[..(len - start) as usize];
+        file.seek(std::io::SeekFrom::Start(start))?;
+        file.read_exact(block)?;
+        match block.iter().rposition(|&b| b != 0) {
+            Some(at) => return Ok(start + at as u64 + 1),
+            None => len = start,
+        }
+    }
+    Ok(0)
+}
+
+impl Write for Mapped {
+    fn write(&mut self, data: &[u8]) -> Result<usize, std::io::Error> {
+        self.reserve(data.len() as u64)?;
+        if let Some(map) = self.map.as_mut() {
+            let at = (self.len - self.base) as usize;
+            map[at..at + data.len()].copy_from_slice(data);
+            self.len += data.len() as u64;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        match &self.map {
+            Some(map) => map.flush_async(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Mapped {
+    fn drop(&mut self) {
+        if let Some(map) = self.map.take() {
+            let _ = map.flush();
+        }
+        // drop the unused part of the last reservation
+        let _ = self.file.set_len(self.len);
+    }
+}
@@ -0,0 +1,181 @@
+//! named loggers of `log2::logger()`, a level set on `db` holds for `db.pool`
+//! too unless that one has its own, the rest follow the global level, and
+//! loggers with bound fields of `log2::with_fields()`, only the level is per
+//! logger, the records go to the sinks of the running instance like any other
+
+use super::{get_level, LevelFilter};
+use log::{Level, Record};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+/// the levels set on named loggers
+static LEVELS: RwLock<BTreeMap<String, LevelFilter>> = RwLock::new(BTreeMap::new());
+/// any level was set, the lookup is skipped otherwise
+static NAMED: AtomicBool = AtomicBool::new(false);
+
+/// a logger of a subsystem, its records have the name as target and module,
+/// and the bound fields as `key=value` after the message, `filter()` on the
+/// target tells them apart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logger {
+    name: String,
+    /// the module of an unnamed logger, the one that created it
+    module: String,
+    fields: Vec<(String, String)>,
+}
+
+/// the logger of a subsystem, dots separate the levels of the hierarchy,
+/// e.g. `db.pool` under `db`, module paths work the same with `::`
+pub fn logger(name: impl Into<String>) -> Logger {
+    Logger {
+        name: name.into(),
+        module: String::new(),
+        fields: Vec::new(),
+    }
+}
+
+/// a logger adding the fields to every record, e.g. the id of a connection,
+/// it has no name, its records are of the module, usually `module_path!()`,
+/// and follow its level
+pub fn with_fields<K, V>(module: &str, fields: impl IntoIterator<Item = (K, V)>) -> Logger
+where
+    K: fmt::Display,
+    V: fmt::Display,
+{
+    let mut logger = logger(String::new()).with_fields(fields);
+    logger.module = module.to_string();
+    logger
+}
+
+impl Logger {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// the logger of `name` below this one, with the same fields
+    pub fn child(&self, name: &str) -> Logger {
+        Logger {
+            name: format!("{}.{name}", self.name),
+            module: String::new(),
+            fields: self.fields.clone(),
+        }
+    }
+
+    /// this logger with more fields bound
+    pub fn with_fields<K, V>(&self, fields: impl IntoIterator<Item = (K, V)>) -> Logger
+    where
+        K: fmt::Display,
+        V: fmt::Display,
+    {
+        let mut logger = self.clone();
+        let fields = fields.into_iter();
+        logger
+            .fields
+            .extend(fields.map(|(k, v)| (k.to_string(), v.to_string())));
+        logger
+    }
+
+    /// the level of this logger and the ones below it without their own
+    pub fn set_level<T: fmt::Display>(&self, level: T) {
+        let level = get_level(level.to_string());
+        if let Ok(mut levels) = LEVELS.write() {
+            levels.insert(self.name.clone(), level);
+        }
+        NAMED.store(true, Ordering::Relaxed);
+        super::widen();
+    }
+
+    /// the effective level, inherited or the global one
+    pub fn level(&self) -> LevelFilter {
+        super::level_of(self.target())
+    }
+
+    /// the name, or the module of an unnamed logger
+    fn target(&self) -> &str {
+        match self.name.is_empty() {
+            true => &self.module,
+            false => &self.name,
+        }
+    }
+
+    pub fn enabled(&self, level: Level) -> bool {
+        level <= self.level()
+    }
+
+    pub fn log(&self, level: Level, args: fmt::Arguments) {
+        if level > log::max_level() {
+            return;
+        }
+        let fields = Fields(&self.fields);
+        let target = self.target();
+        log::logger().log(
+            &Record::builder()
+                .args(format_args!("{args}{fields}"))
+                .level(level)
+                .target(target)
+                .module_path(Some(target))
+                .build(),
+        );
+    }
+
+    pub fn error(&self, args: fmt::Arguments) {
+        self.log(Level::Error, args);
+    }
+
+    pub fn warn(&self, args: fmt::Arguments) {
+        self.log(Level::Warn, args);
+    }
+
+    pub fn info(&self, args: fmt::Arguments) {
+        self.log(Level::Info, args);
+    }
+
+    pub fn debug(&self, args: fmt::Arguments) {
+        self.log(Level::Debug, args);
+    }
+
+    pub fn trace(&self, args: fmt::Arguments) {
+        self.log(Level::Trace, args);
+    }
+}
+
+/// bound fields the way enrichers render them
+struct Fields<'a>(&'a [(String, String)]);
+
+impl fmt::Display for Fields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in self.0 {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// the level of the name or its closest parent with one
+pub(crate) fn level(name: &str) -> Option<LevelFilter> {
+    if !NAMED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let levels = LEVELS.read().ok()?;
+    let mut name = name;
+    loop {
+        if let Some(level) = levels.get(name) {
+            return Some(*level);
+        }
+        let parent = [".", "::"]
+            .iter()
+            .filter_map(|separator| name.rfind(separator))
+            .max()?;
+        name = &name[..parent];
+    }
+}
+
+/// the most verbose level of the named loggers
+pub(crate) fn widest() -> LevelFilter {
+    match LEVELS.read() {
+        Ok(levels) => levels.values().copied().max().unwrap_or(LevelFilter::Off),
+        Err(_) => LevelFilter::Off,
+    }
+}
@@ -0,0 +1,333 @@
+//! names of the rotated files for `Log2::naming()`
+
+use super::fs::Fs;
+use super::Context;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// how the rotated files are named
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Naming {
+    /// `log.1.txt` is the newest, every rotation shifts the older ones
+    #[default]
+    Index,
+    /// `log.01.txt` to `log.10.txt`, zero-padded so that `ls` sorts them in order
+    Padded,
+    /// `log.2024-06-01_14-03.txt`, the older ones stay untouched
+    Date,
+}
+
+/// the names of `Log2::rotation_name()`, numbered upwards, the rotated files
+/// of this process are remembered to remove the oldest ones
+pub(crate) struct RotationName {
+    name: Box<dyn Fn(usize, std::time::SystemTime) -> String + Send + Sync>,
+    next: AtomicUsize,
+    archives: std::sync::Mutex<std::collections::VecDeque<PathBuf>>,
+}
+
+impl RotationName {
+    pub(crate) fn new(
+        name: impl Fn(usize, std::time::SystemTime) -> String + Send + Sync + 'static,
+    ) -> Self {
+        RotationName {
+            name: Box::new(name),
+            next: AtomicUsize::new(1),
+            archives: Default::default(),
+        }
+    }
+
+    /// the next free name, relative ones are next to the active file
+    fn name(&self, fs: &dyn Fs, path: &Path) -> PathBuf {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let time = std::time::SystemTime::now();
+        loop {
+            let index = self.next.fetch_add(1, Ordering::Relaxed);
+            let name = dir.join((self.name)(index, time));
+            if !fs.exists(&name) {
+                return name;
+            }
+        }
+    }
+}
+
+/// renames from the first path to the second one, or removals, which move
+/// the active file out of the way
+pub(crate) fn moves(ctx: &Context) -> Vec<(PathBuf, Option<PathBuf>)> {
+    let path = &ctx.path;
+    if ctx.count < 2 {
+        return Vec::new();
+    }
+    if let Some(custom) = &ctx.rotation_name {
+        let name = custom.name(&*ctx.fs, path);
+        let mut moves = vec![(path.clone(), Some(name.clone()))];
+        if let Ok(mut archives) = custom.archives.lock() {
+            archives.push_front(name);
+            while archives.len() > ctx.count - 1 {
+                moves.extend(archives.pop_back().map(|old| (old, None)));
+            }
+        }
+        return compressed(ctx, moves);
+    }
+    let moves = match ctx.naming {
+        Naming::Index | Naming::Padded => {
+            let width = width(ctx.count, ctx.naming);
+            // the oldest is removed rather than renamed over, for `Log2::on_prune()`
+            let oldest = (segment(path, ctx.count - 1, width), None);
+            let shifts = (0..ctx.count - 1)
+                .rev()
+                .map(|i| (segment(path, i, width), Some(segment(path, i + 1, width))));
+            std::iter::once(oldest).chain(shifts).collect()
+        }
+        Naming::Date => {
+            let mut moves = vec![(path.clone(), Some(dated(&*ctx.fs, path)))];
+            // room for the new one
+            let stale = archives(&*ctx.fs, path, ctx.count, ctx.naming, None)
+                .into_iter()
+                .skip(ctx.count - 2);
+            moves.extend(stale.map(|old| (old, None)));
+            moves
+        }
+    };
+    compressed(ctx, moves)
+}
+
+/// endings of `Log2::compress()` and `Log2::encrypt_archives()`
+const COMPRESSED: [&str; 5] = [".gz.enc", ".lz4.enc", ".enc", ".gz", ".lz4"];
+
+/// the compressed or encrypted files move along with the others
+fn compressed(
+    ctx: &Context,
+    moves: Vec<(PathBuf, Option<PathBuf>)>,
+) -> Vec<(PathBuf, Option<PathBuf>)> {
+    use super::compress::suffixed;
+
+    let Some(compressor) = &ctx.compressor else {
+        return moves;
+    };
+    let suffixes = compressor.suffixes();
+    moves
+        .into_iter()
+        .flat_map(|(a, b)| {
+            let twins = suffixes.iter().map(|suffix| {
+                let b = b.as_deref().map(|b| suffixed(b, suffix));
+                (suffixed(&a, suffix), b)
+            });
+            std::iter::once((a.clone(), b.clone())).chain(twins.collect::<Vec<_>>())
+        })
+        .collect()
+}
+
+/// the rotated files, newest first
+pub(crate) fn archives(
+    fs: &dyn Fs,
+    path: &Path,
+    count: usize,
+    naming: Naming,
+    custom: Option<&RotationName>,
+) -> Vec<PathBuf> {
+    if let Some(custom) = custom {
+        return match custom.archives.lock() {
+            Ok(archives) => archives.iter().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+    }
+    match naming {
+        Naming::Index | Naming::Padded => {
+            let width = width(count, naming);
+            (1..count).map(|i| segment(path, i, width)).collect()
+        }
+        Naming::Date => {
+            let (stem, extension) = parts(path);
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            let Ok(entries) = fs.list(dir) else {
+                return Vec::new();
+            };
+            let mut archives: Vec<_> = entries
+                .into_iter()
+                .filter_map(|entry| {
+                    let name = entry.file_name()?.to_str()?;
+                    // or its compressed file of `Log2::compress()`
+                    let name = COMPRESSED
+                        .iter()
+                        .find_map(|c| name.strip_suffix(c))
+                        .unwrap_or(name);
+                    let stamp = name
+                        .strip_prefix(&stem)?
+                        .strip_suffix(&extension)?
+                        .strip_prefix('.')?;
+                    let shape = |c: char| c.is_ascii_digit() || c == '-' || c == '_';
+                    let dated = stamp.contains('_') && stamp.chars().all(shape);
+                    let modified = fs.metadata(&entry).ok()?.modified;
+                    dated.then_some((modified, entry))
+                })
+                .collect();
+            archives.sort();
+            archives.into_iter().rev().map(|(_, path)| path).collect()
+        }
+    }
+}
+
+/// digits of the indices, the largest one sets them when padded
+fn width(count: usize, naming: Naming) -> usize {
+    match naming {
+        Naming::Padded => count.saturating_sub(1).to_string().len(),
+        _ => 0,
+    }
+}
+
+/// path of the n-th segment, 0 is the active file: log.txt, log.1.txt, log.2.txt
+fn segment(path: &Path, index: usize, width: usize) -> PathBuf {
+    if index == 0 {
+        return path.into();
+    }
+    let (Some(stem), extension) = (path.file_stem(), path.extension()) else {
+        return path.into();
+    };
+    let mut name = stem.to_owned();
+    name.push(format!(".{index:0width$}"));
+    if let Some(extension) = extension {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+/// the path with the process id before the extension: app.log, app.4321.log
+pub(crate) fn unique(path: &Path) -> PathBuf {
+    let Some(stem) = path.file_stem() else {
+        return path.into();
+    };
+    let mut name = stem.to_owned();
+    name.push(format!(".{}", std::process::id()));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+/// the stem and the dotted extension of a file, lossy for the matching
+fn parts(path: &Path) -> (String, String) {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = match path.extension() {
+        Some(extension) => format!(".{}", extension.to_string_lossy()),
+        None => String::new(),
+    };
+    (stem.into_owned(), extension)
+}
+
+/// a free name stamped with the current time, `-1`, `-2` and so on are
+/// appended for rotations within the same minute, an archive kept by
+/// `Log2::on_prune()` gets one too, which takes it out of the rotation
+pub(crate) fn dated(fs: &dyn Fs, path: &Path) -> PathBuf {
+    let stamp = stamp(std::time::SystemTime::now());
+    let stem = path.file_stem().unwrap_or_default();
+    let mut n = 0;
+    loop {
+        let mut name = stem.to_owned();
+        name.push(".");
+        name.push(&stamp);
+        if n > 0 {
+            name.push(format!("-{n}"));
+        }
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        let dated = path.with_file_name(name);
+        if !fs.exists(&dated) {
+            return dated;
+        }
+        n += 1;
+    }
+}
+
+/// local time to the minute
+#[cfg(feature = "chrono")]
+pub(crate) fn stamp(time: std::time::SystemTime) -> String {
+    let time: chrono::DateTime<chrono::Local> = time.into();
+    time.format("%Y-%m-%d_%H-%M").to_string()
+}
+
+/// utc to the minute, without chrono
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn stamp(time: std::time::SystemTime) -> String {
+    let [year, month, day, hour, minute, _] = civil(time);
+    format!("{year:04}-{month:02}-{day:02}_{hour:02}-{minute:02}")
+}
+
+/// year, month, day, hour, minute and second in utc
+#[cfg(not(feature = "chrono"))]
+pub(crate) fn civil(time: std::time::SystemTime) -> [i64; 6] {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, secs) = ((secs / 86400) as i64, (secs % 86400) as i64);
+    // days to the civil date, after Howard Hinnant
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    [year, month, day, secs / 3600, secs % 3600 / 60, secs % 60]
+}
+
+/// a path of `log2::template()` for now, `{hostname}` and `{pid}` are
+/// replaced, and the strftime fields of the local time like `%Y-%m-%d`
+pub(crate) fn expand(template: &str) -> PathBuf {
+    let mut path = template.replace("{pid}", &std::process::id().to_string());
+    if path.contains("{hostname}") {
+        path = path.replace("{hostname}", &super::hostname());
+    }
+    PathBuf::from(strftime(&path, std::time::SystemTime::now()))
+}
+
+/// the local time, a template with invalid fields stays as it is
+#[cfg(feature = "chrono")]
+fn strftime(template: &str, time: std::time::SystemTime) -> String {
+    use std::fmt::Write;
+
+    let time: chrono::DateTime<chrono::Local> = time.into();
+    let mut text = String::new();
+    match write!(text, "{}", time.format(template)) {
+        Ok(_) => text,
+        Err(_) => template.to_string(),
+    }
+}
+
+/// `%Y`, `%m`, `%d`, `%H`, `%M`, `%S` and `%%` in utc, without chrono
+#[cfg(not(feature = "chrono"))]
+fn strftime(template: &str, time: std::time::SystemTime) -> String {
+    let [year, month, day, hour, minute, second] = civil(time);
+    let mut text = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => text += &format!("{year:04}"),
+            Some('m') => text += &format!("{month:02}"),
+            Some('d') => text += &format!("{day:02}"),
+            Some('H') => text += &format!("{hour:02}"),
+            Some('M') => text += &format!("{minute:02}"),
+            Some('S') => text += &format!("{second:02}"),
+            Some('%') => text.push('%'),
+            Some(c) => {
+                text.push('%');
+                text.push(c);
+            }
+            None => text.push('%'),
+        }
+    }
+    text
+}
@@ -0,0 +1,28 @@
+//! custom rotation schemes for `Log2::rotation_policy()`
+
+use std::path::Path;
+use std::time::SystemTime;
+
+/// the active file when a `RotationPolicy` is asked
+#[derive(Debug, Clone, Copy)]
+pub struct RotationState<'a> {
+    pub path: &'a Path,
+    /// bytes written into the file
+    pub size: u64,
+    /// records written into the file since it was opened
+    pub records: u64,
+    /// when the file was opened
+    pub opened: SystemTime,
+    pub now: SystemTime,
+}
+
+/// when the active file is rotated and how the rotated files are named, e.g.
+/// at the market close or per job, the size limit of `size()` applies as well
+pub trait RotationPolicy: Send + Sync {
+    /// rotate the file now, asked after every write
+    fn should_rotate(&self, state: &RotationState) -> bool;
+
+    /// the name of the n-th rotated file of this process, like the names of
+    /// `Log2::rotation_name()`
+    fn next_name(&self, index: usize, time: SystemTime) -> String;
+}
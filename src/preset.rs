@@ -0,0 +1,242 @@
+//! curated layouts for `Log2::preset()`
+
+use super::{Formatter, Parts};
+use log::Level;
+use std::fmt::Write;
+
+/// built-in layouts, `Full` is the default one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Format {
+    /// `[2023-06-01 10:00:00.000] [INFO] [app] message`
+    #[default]
+    Full,
+    /// `10:00:00.000 I message`
+    Compact,
+    /// `2023-06-01 10:00:00.000  INFO   app  message`, aligned columns
+    Pretty,
+    /// one json object per line
+    Json,
+    /// `ts=2023-06-01T10:00:00.000 level=info module=app msg="message"`
+    Logfmt,
+}
+
+impl Formatter for Format {
+    fn format(&self, line: &mut String, parts: &Parts) {
+        match self {
+            Format::Full => {
+                let _ = write!(
+                    line,
+                    "[{}] [{}] [{}] {}",
+                    parts.time, parts.label, parts.module, parts.message
+                );
+            }
+            Format::Compact => {
+                let time = parts
+                    .time
+                    .split_once(' ')
+                    .map_or(parts.time, |(_, time)| time);
+                let icon = &parts.level.as_str()[..1];
+                let _ = write!(line, "{time} {icon} {}", parts.message);
+            }
+            Format::Pretty => {
+                let _ = write!(
+                    line,
+                    "{}  {:<5}  {:<24}  {}",
+                    parts.time, parts.label, parts.module, parts.message
+                );
+            }
+            Format::Json => json(line, parts),
+            Format::Logfmt => logfmt(line, parts),
+        }
+    }
+}
+
+fn json(line: &mut String, parts: &Parts) {
+    line.push_str("{\"time\":");
+    quote(line, parts.time);
+    line.push_str(",\"level\":");
+    quote(line, parts.level.as_str());
+    line.push_str(",\"module\":");
+    quote(line, parts.module);
+    if parts.sequence > 0 {
+        let _ = write!(line, ",\"sequence\":{}", parts.sequence);
+    }
+    if !parts.hostname.is_empty() {
+        line.push_str(",\"hostname\":");
+        quote(line, parts.hostname);
+    }
+    if parts.pid > 0 {
+        let _ = write!(line, ",\"pid\":{}", parts.pid);
+    }
+    if !parts.thread_name.is_empty() {
+        line.push_str(",\"thread\":");
+        quote(line, parts.thread_name);
+        let _ = write!(line, ",\"thread_id\":{}", parts.thread_id);
+    }
+    line.push_str(",\"message\":");
+    quote(line, parts.message);
+    line.push('}');
+}
+
+fn logfmt(line: &mut String, parts: &Parts) {
+    line.push_str("ts=");
+    value(line, &parts.time.replacen(' ', "T", 1));
+    line.push_str(" level=");
+    line.push_str(&parts.level.as_str().to_lowercase());
+    line.push_str(" module=");
+    value(line, parts.module);
+    if parts.sequence > 0 {
+        let _ = write!(line, " sequence={}", parts.sequence);
+    }
+    if !parts.hostname.is_empty() {
+        line.push_str(" hostname=");
+        value(line, parts.hostname);
+    }
+    if parts.pid > 0 {
+        let _ = write!(line, " pid={}", parts.pid);
+    }
+    if !parts.thread_name.is_empty() {
+        line.push_str(" thread=");
+        value(line, parts.thread_name);
+        let _ = write!(line, " thread_id={}", parts.thread_id);
+    }
+    line.push_str(" msg=");
+    value(line, parts.message);
+}
+
+/// a logfmt value, quoted when empty or when it has spaces, `=`, quotes or
+/// control characters
+fn value(line: &mut String, text: &str) {
+    let bare = |c: char| c > ' ' && c != '=' && c != '"' && c != '\\' && c != '\u{7f}';
+    match !text.is_empty() && text.chars().all(bare) {
+        true => line.push_str(text),
+        false => quote(line, text),
+    }
+}
+
+/// a json string with the escapes
+fn quote(line: &mut String, text: &str) {
+    line.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(line, "\\u{:04x}", c as u32);
+            }
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+}
+
+/// ArcSight CEF events for a SIEM, `Log2::format(Cef::new("Acme", "Gateway", "1.0"))`,
+/// the module is the signature id, the message the name, and the `key=value`
+/// fields of the message mapped by `field()` become extensions
+#[derive(Debug, Clone)]
+pub struct Cef {
+    vendor: String,
+    product: String,
+    version: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Cef {
+    pub fn new(vendor: &str, product: &str, version: &str) -> Self {
+        Cef {
+            vendor: vendor.into(),
+            product: product.into(),
+            version: version.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// map a field of the message to an extension key, e.g. `user` to `suser`
+    /// or `ip` to `src`, the field is taken out of the name
+    pub fn field(mut self, key: &str, extension: &str) -> Self {
+        self.fields.push((key.into(), extension.into()));
+        self
+    }
+
+    fn extension<'a>(&'a self, token: &'a str) -> Option<(&'a str, &'a str)> {
+        let (key, value) = token.split_once('=')?;
+        let (_, extension) = self.fields.iter().find(|(k, _)| k == key)?;
+        Some((extension, value))
+    }
+}
+
+impl Formatter for Cef {
+    fn format(&self, line: &mut String, parts: &Parts) {
+        // a syslog-like prefix, as the connectors expect
+        let _ = write!(line, "{} ", parts.time);
+        if !parts.hostname.is_empty() {
+            let _ = write!(line, "{} ", parts.hostname);
+        }
+        let severity = match parts.level {
+            Level::Error => 8,
+            Level::Warn => 5,
+            Level::Info => 3,
+            Level::Debug => 1,
+            Level::Trace => 0,
+        };
+        let mut name = Vec::new();
+        let mut extensions = Vec::new();
+        for token in parts.message.split(' ') {
+            match self.extension(token) {
+                Some(extension) => extensions.push(extension),
+                None => name.push(token),
+            }
+        }
+        line.push_str("CEF:0|");
+        for field in [&self.vendor, &self.product, &self.version, parts.module] {
+            header(line, field);
+            line.push('|');
+        }
+        header(line, &name.join(" "));
+        let _ = write!(line, "|{severity}|");
+        let pid = parts.pid.to_string();
+        if parts.pid > 0 {
+            extensions.push(("dvcpid", &pid));
+        }
+        for (i, (key, value)) in extensions.into_iter().enumerate() {
+            if i > 0 {
+                line.push(' ');
+            }
+            let _ = write!(line, "{key}=");
+            extension(line, value);
+        }
+    }
+}
+
+/// a field of the header, `|` and `\` escaped
+fn header(line: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '|' => line.push_str("\\|"),
+            '\\' => line.push_str("\\\\"),
+            '\n' | '\r' => line.push(' '),
+            c => line.push(c),
+        }
+    }
+}
+
+/// a value of an extension, `=` and `\` escaped
+fn extension(line: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '=' => line.push_str("\\="),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            c => line.push(c),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! the daily byte budget of `Log2::daily_quota()`, once it is spent only
+//! warnings and errors reach the file until the next day
+
+use log::Level;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+pub(crate) struct Quota {
+    pub(crate) limit: u64,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// the minute of the last record, the day is only looked at once a minute
+    minute: u64,
+    day: String,
+    /// written to the file today
+    bytes: u64,
+}
+
+impl Quota {
+    pub(crate) fn new(limit: u64) -> Self {
+        Quota {
+            limit,
+            state: Default::default(),
+        }
+    }
+
+    /// whether a record of the level still goes to the file
+    pub(crate) fn allows(&self, level: Level, time: SystemTime) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.roll(time);
+        level <= Level::Warn || state.bytes < self.limit
+    }
+
+    /// count a line of the file, true once it used up the budget
+    pub(crate) fn spend(&self, bytes: u64) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let spent = state.bytes >= self.limit;
+        state.bytes += bytes;
+        !spent && state.bytes >= self.limit
+    }
+}
+
+/// the note in the file once the budget is spent
+pub(crate) fn used_up(limit: u64) -> String {
+    format!("the daily quota of {limit} bytes is used up, only warnings and errors are written until tomorrow")
+}
+
+impl State {
+    /// start over on a new day
+    fn roll(&mut self, time: SystemTime) {
+        let secs = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if secs / 60 == self.minute && !self.day.is_empty() {
+            return;
+        }
+        self.minute = secs / 60;
+        let mut day = super::naming::stamp(time);
+        day.truncate(10);
+        if day != self.day {
+            self.day = day;
+            self.bytes = 0;
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! flight recorder for `Log2::flight_recorder()`, the last records below the
+//! level stay in memory and are replayed in front of the next error
+
+use super::{Action, Context, Entry};
+use log::Level;
+use std::collections::VecDeque;
+
+pub(crate) struct Recorder {
+    capacity: usize,
+    entries: VecDeque<Entry>,
+    scratch: Vec<Action>,
+}
+
+impl Recorder {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// keep the remembered records, and put them back as records in front of
+    /// an error or a dump
+    pub(crate) fn replay(&mut self, ctx: &Context, actions: &mut Vec<Action>) {
+        std::mem::swap(actions, &mut self.scratch);
+        for action in self.scratch.drain(..) {
+            match action {
+                Action::Remember(entry) => {
+                    if self.entries.len() == self.capacity {
+                        if let Some(old) = self.entries.pop_front() {
+                            ctx.counters.handled();
+                            ctx.pool.give(old.message);
+                        }
+                    }
+                    self.entries.push_back(entry);
+                }
+                Action::Dump => actions.extend(self.entries.drain(..).map(Action::Record)),
+                Action::Record(entry) if entry.level == Level::Error => {
+                    actions.extend(self.entries.drain(..).map(Action::Record));
+                    actions.push(Action::Record(entry));
+                }
+                action => actions.push(action),
+            }
+        }
+    }
+}
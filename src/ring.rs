@@ -0,0 +1,156 @@
+//! fixed ring of preallocated records for `Log2::non_blocking()`, pushing never
+//! blocks nor allocates, a record is dropped when the ring is full
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// bytes of message kept per record, longer messages are truncated
+pub(crate) const FRAME: usize = 512;
+/// bytes of module path kept per record, the named loggers and `with_fields()`
+/// have modules that aren't `'static`
+const MODULE: usize = 128;
+
+pub(crate) struct Frame {
+    pub(crate) sequence: u64,
+    pub(crate) thread: Option<super::Thread>,
+    pub(crate) level: log::Level,
+    module_len: usize,
+    module: [u8; MODULE],
+    pub(crate) time: std::time::SystemTime,
+    pub(crate) len: usize,
+    pub(crate) buf: [u8; FRAME],
+}
+
+/// the length of `s` cut to `max` bytes, never in the middle of a character
+fn cut(s: &str, max: usize) -> usize {
+    let mut n = s.len().min(max);
+    while !s.is_char_boundary(n) {
+        n -= 1;
+    }
+    n
+}
+
+impl Frame {
+    pub(crate) fn set_module(&mut self, module: &str) {
+        let n = cut(module, MODULE);
+        self.module[..n].copy_from_slice(&module.as_bytes()[..n]);
+        self.module_len = n;
+    }
+
+    pub(crate) fn module(&self) -> &str {
+        std::str::from_utf8(&self.module[..self.module_len]).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Write for Frame {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let n = cut(s, FRAME - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+struct Slot {
+    seq: AtomicUsize,
+    frame: UnsafeCell<Frame>,
+}
+
+/// bounded multi-producer queue, Dmitry Vyukov's sequence-per-slot design
+pub(crate) struct Ring {
+    slots: Box<[Slot]>,
+    mask: usize,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+// a slot's frame is only touched by the thread that claimed it through `seq`
+unsafe impl Sync for Ring {}
+
+impl Ring {
+    /// capacity is rounded up to a power of two
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2).next_power_of_two();
+        let slots = (0..capacity)
+            .map(|i| Slot {
+                seq: AtomicUsize::new(i),
+                frame: UnsafeCell::new(Frame {
+                    sequence: 0,
+                    thread: None,
+                    level: log::Level::Trace,
+                    module_len: 0,
+                    module: [0; MODULE],
+                    time: std::time::UNIX_EPOCH,
+                    len: 0,
+                    buf: [0; FRAME],
+                }),
+            })
+            .collect();
+        Self {
+            slots,
+            mask: capacity - 1,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// claim a free slot and let `fill` write the record, false when the ring is full
+    pub(crate) fn push(&self, fill: impl FnOnce(&mut Frame)) -> bool {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            let diff = seq as isize - pos as isize;
+            if diff == 0 {
+                match self.head.compare_exchange_weak(
+                    pos,
+                    pos + 1,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let frame = unsafe { &mut *slot.frame.get() };
+                        frame.len = 0;
+                        fill(frame);
+                        slot.seq.store(pos + 1, Ordering::Release);
+                        return true;
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return false;
+            } else {
+                pos = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// take the oldest record out of the ring
+    pub(crate) fn pop<T>(&self, read: impl FnOnce(&Frame) -> T) -> Option<T> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            let diff = seq as isize - (pos + 1) as isize;
+            if diff == 0 {
+                match self.tail.compare_exchange_weak(
+                    pos,
+                    pos + 1,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let value = read(unsafe { &*slot.frame.get() });
+                        slot.seq.store(pos + self.mask + 1, Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return None;
+            } else {
+                pos = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! the rotation of the worker without the logger, for any writer
+
+use super::{context, Direct, Log2};
+use std::io::Write;
+use std::path::Path;
+
+/// a file rotated like the one of log2, e.g. the target of `env_logger`, a
+/// CSV writer or the output of a child process, it rotates after a write that
+/// ends a line, so lines are never split across files
+pub struct RotatingFile {
+    direct: Direct,
+}
+
+impl RotatingFile {
+    /// the file with the defaults of `log2::open()`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        Self::with(super::open(path))
+    }
+
+    /// the file of the builder with its size, rotation, naming, compression,
+    /// header and footer, the settings of the records don't apply
+    pub fn with(logger: Log2) -> Result<Self, std::io::Error> {
+        if logger.path.as_os_str().is_empty() {
+            let message = "a rotating file needs a path";
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                message,
+            ));
+        }
+        logger.fs.create(&logger.path)?;
+        let direct = Direct::open(context(&logger))?;
+        Ok(RotatingFile { direct })
+    }
+
+    /// the active file
+    pub fn path(&self) -> &Path {
+        &self.direct.ctx.path
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
+        self.direct.append(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        match self.direct.target.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! tamper evidence for `Log2::hmac()`, every line of the file ends with a
+//! HMAC-SHA256 over the mac of the line before and the line itself
+
+use hmac::{Hmac, Mac};
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::sync::Mutex;
+
+type HmacSha256 = Hmac<sha2::Sha256>;
+
+/// hex digits of a mac
+const HEX: usize = 64;
+
+pub(crate) struct Chain {
+    key: Vec<u8>,
+    /// the mac of the last line in the active file
+    last: Mutex<[u8; 32]>,
+}
+
+impl Chain {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        Chain {
+            key: key.to_vec(),
+            last: Mutex::new([0; 32]),
+        }
+    }
+
+    /// go on from the last line of a file opened for appending, a new file
+    /// starts from zeros
+    pub(crate) fn resume(&self, path: &Path) -> Result<(), std::io::Error> {
+        let mut file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut tail = Vec::new();
+        file.seek(std::io::SeekFrom::Start(len.saturating_sub(HEX as u64 + 2)))?;
+        file.read_to_end(&mut tail)?;
+        let tail = String::from_utf8_lossy(&tail);
+        let tail = tail.trim_end_matches(['\r', '\n']);
+        let last = tail
+            .get(tail.len().saturating_sub(HEX)..)
+            .and_then(decode)
+            .unwrap_or([0; 32]);
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = last;
+        Ok(())
+    }
+
+    /// append the mac before the line ending
+    pub(crate) fn seal(&self, line: &mut String) {
+        let ending = match line.ends_with("\r\n") {
+            true => "\r\n",
+            false => "\n",
+        };
+        line.truncate(line.len() - ending.len().min(line.len()));
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        *last = mac(&self.key, &last, line.as_bytes());
+        line.push(' ');
+        line.push_str(&encode(&last));
+        line.push_str(ending);
+    }
+}
+
+fn mac(key: &[u8], last: &[u8; 32], line: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("hmac takes keys of any size");
+    mac.update(last);
+    mac.update(line);
+    mac.finalize().into_bytes().into()
+}
+
+fn encode(mac: &[u8; 32]) -> String {
+    mac.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode(hex: &str) -> Option<[u8; 32]> {
+    let mut mac = [0; 32];
+    for (i, byte) in mac.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(mac)
+}
+
+/// check the chain of a file written with `Log2::hmac()`, the number of
+/// records is returned, an edited, inserted or removed line fails with the
+/// line where the chain breaks, lines of a multi-line record are covered by
+/// the mac of its last line
+pub fn verify(path: impl AsRef<Path>, key: impl AsRef<[u8]>) -> Result<u64, std::io::Error> {
+    let data = std::fs::read(path)?;
+    let text = String::from_utf8_lossy(&data);
+    let broken = |line: usize| {
+        let message = format!("the hmac chain is broken at line {line}");
+        std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+    };
+
+    let mut last = [0; 32];
+    let mut records = 0;
+    // the lines of the record so far
+    let mut record = String::new();
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let content = line.trim_end_matches('\n').trim_end_matches('\r');
+        let sealed = content
+            .len()
+            .checked_sub(HEX + 1)
+            .filter(|&at| content.as_bytes()[at] == b' ')
+            .and_then(|at| Some((&content[..at], decode(content.get(at + 1..)?)?)));
+        match sealed {
+            Some((content, sealed)) => {
+                record.push_str(content);
+                if mac(key.as_ref(), &last, record.as_bytes()) != sealed {
+                    return Err(broken(i + 1));
+                }
+                last = sealed;
+                records += 1;
+                record.clear();
+            }
+            None if line.ends_with('\n') => record.push_str(line),
+            // the last line is not sealed
+            None => return Err(broken(i + 1)),
+        }
+    }
+    if !record.is_empty() {
+        return Err(broken(text.lines().count()));
+    }
+    Ok(records)
+}
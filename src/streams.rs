@@ -0,0 +1,38 @@
+//! stdout and stderr of the process into the log for `Log2::capture_std_streams()`,
+//! the descriptors are redirected into pipes, so the prints of C libraries
+//! and child processes sharing them are caught too
+
+use std::os::fd::FromRawFd;
+use std::os::raw::c_int;
+
+extern "C" {
+    fn pipe(fds: *mut c_int) -> c_int;
+    fn dup2(old: c_int, new: c_int) -> c_int;
+    fn close(fd: c_int) -> c_int;
+}
+
+/// redirect both streams, stdout is logged at info and stderr at warn
+pub(crate) fn capture() -> Result<(), std::io::Error> {
+    redirect(1, "stdout", log::Level::Info)?;
+    redirect(2, "stderr", log::Level::Warn)
+}
+
+/// a pipe in place of the descriptor, every line read from it becomes a
+/// record with the stream as its target
+fn redirect(fd: c_int, target: &'static str, level: log::Level) -> Result<(), std::io::Error> {
+    let mut fds = [0; 2];
+    if unsafe { pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let [read, write] = fds;
+    let redirected = unsafe { dup2(write, fd) } >= 0;
+    let error = std::io::Error::last_os_error();
+    unsafe { close(write) };
+    if !redirected {
+        unsafe { close(read) };
+        return Err(error);
+    }
+    // the descriptor keeps the write end open, so the reader runs for good
+    let pipe = unsafe { std::fs::File::from_raw_fd(read) };
+    super::child::forward(pipe, target.to_string(), level)
+}
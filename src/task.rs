@@ -0,0 +1,238 @@
+//! tokio worker, the async twin of `worker()` for `Log2::start_tokio()`
+
+use super::recorder::Recorder;
+use super::{
+    current, due, filled, footer, header, link, lock, low_disk, opened, poll_timeout, protect,
+    render, resume, retarget, ring_entry, shift, space, Action, Clock, Context, SyncPolicy,
+};
+use std::sync::atomic::Ordering;
+use tokio::io::AsyncWriteExt;
+
+type Target = tokio::io::BufWriter<tokio::fs::File>;
+
+/// the blocking calls, locks, renames and callbacks, a multi-thread runtime
+/// moves its other tasks off this thread meanwhile, `Log2::start_tokio()`
+/// refuses the ones that might block for long on a current-thread runtime
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::current().runtime_flavor() {
+        tokio::runtime::RuntimeFlavor::CurrentThread => f(),
+        _ => tokio::task::block_in_place(f),
+    }
+}
+
+async fn rotate(ctx: &mut Context) -> Result<Target, std::io::Error> {
+    blocking(|| retarget(ctx))?;
+    let size = tokio::fs::metadata(&ctx.path).await?.len();
+    let fresh = ctx.fresh.swap(false, Ordering::Relaxed) && size > 0;
+
+    if size >= ctx.size || fresh {
+        if let Some(footer) = footer(ctx, size) {
+            let mut file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&ctx.path)
+                .await?;
+            file.write_all(footer.as_bytes()).await?;
+            ctx.counters.written(footer.len() as u64);
+        }
+        blocking(|| shift(ctx));
+        ctx.counters.rotated();
+    }
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    if let Some(mode) = ctx.mode {
+        options.mode(mode);
+    }
+    let mut file = options.open(&ctx.path).await?;
+    ctx.records.store(0, Ordering::Relaxed);
+    ctx.opened = std::time::SystemTime::now();
+    opened(ctx, &file.metadata().await?);
+    blocking(|| {
+        protect(ctx)?;
+        link(ctx);
+        resume(ctx)
+    })?;
+    if let Some(header) = header(ctx) {
+        file.write_all(header.as_bytes()).await?;
+        ctx.counters.written(header.len() as u64);
+    }
+
+    Ok(tokio::io::BufWriter::with_capacity(ctx.buffer, file))
+}
+
+async fn sync(file: &mut Target) -> Result<(), std::io::Error> {
+    file.flush().await?;
+    file.get_ref().sync_data().await
+}
+
+pub(crate) async fn worker(
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<Action>,
+    mut ctx: Context,
+) -> Result<(), std::io::Error> {
+    let mut target = None;
+    let mut size: u64 = 0;
+
+    if !ctx.path.as_os_str().is_empty() {
+        let _lock = blocking(|| lock(&ctx))?;
+        let file = rotate(&mut ctx).await?;
+        size = filled(&ctx, file.get_ref().metadata().await?.len());
+        target = Some(file);
+    }
+
+    // wake up at least every second, or as often as the flush interval demands
+    let timeout = poll_timeout(&ctx);
+    let mut ts = std::time::Instant::now();
+    let mut synced = ts;
+    let mut unflushed = false;
+    let mut dirty = false;
+    let mut clock = Clock::default();
+    let mut recorder = (ctx.recorder > 0).then(|| Recorder::new(ctx.recorder));
+    let mut actions = Vec::new();
+
+    loop {
+        let action = match tokio::time::timeout(timeout, rx.recv()).await {
+            Ok(Some(action)) => Some(action),
+            // every sender is gone
+            Ok(None) => break,
+            Err(_) => None,
+        };
+
+        // records in the ring were logged before the action just received
+        if let Some(ring) = &ctx.ring {
+            while let Some(entry) = ring.pop(|frame| ring_entry(&ctx, frame)) {
+                actions.extend(entry.map(Action::Record));
+            }
+        }
+        actions.extend(action);
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.replay(&ctx, &mut actions);
+        }
+
+        let mut exit = false;
+        for action in actions.drain(..) {
+            match action {
+                Action::Record(entry) => {
+                    let (tee, line) = render(&ctx, &mut clock, entry, target.is_some());
+                    if let Some(tee) = tee {
+                        println!("{tee}");
+                    }
+                    let Some(line) = line else {
+                        continue;
+                    };
+                    // follow another process of `shared()` to its new file
+                    let _lock = match ctx.shared {
+                        true => blocking(|| lock(&ctx))?,
+                        false => None,
+                    };
+                    if ctx.shared && !blocking(|| current(&ctx)) {
+                        target = Some(rotate(&mut ctx).await?);
+                    }
+                    if let Some(file) = target.as_mut() {
+                        file.write_all(line.as_bytes()).await?;
+                        size += line.len() as u64;
+                        ctx.counters.written(line.len() as u64);
+                        ctx.pool.give(line);
+                        unflushed = true;
+                        dirty = true;
+                        if ctx.sync == SyncPolicy::EveryWrite {
+                            sync(file).await?;
+                            dirty = false;
+                        }
+                        if ctx.shared {
+                            file.flush().await?;
+                            unflushed = false;
+                            size = tokio::fs::metadata(&ctx.path).await?.len();
+                        }
+                        if due(&ctx, size) {
+                            file.flush().await?;
+                            if dirty && ctx.sync != SyncPolicy::Never {
+                                sync(file).await?;
+                                dirty = false;
+                            }
+                            let file = rotate(&mut ctx).await?;
+                            size = filled(&ctx, file.get_ref().metadata().await?.len());
+                            unflushed = false;
+                            target = Some(file);
+                        }
+                    }
+                }
+                Action::Flush(done) => {
+                    if let Some(file) = target.as_mut() {
+                        file.flush().await?;
+                        unflushed = false;
+                        if dirty && ctx.sync != SyncPolicy::Never {
+                            sync(file).await?;
+                            dirty = false;
+                        }
+                    }
+                    done();
+                }
+                Action::Exit => {
+                    exit = true;
+                    break;
+                }
+                Action::Redirect(path) => {
+                    if let Some(file) = target.as_mut() {
+                        file.flush().await?;
+                        if dirty && ctx.sync != SyncPolicy::Never {
+                            sync(file).await?;
+                            dirty = false;
+                        }
+                    }
+                    ctx.path = path;
+                    ctx.template = None;
+                    let _lock = blocking(|| lock(&ctx))?;
+                    let file = rotate(&mut ctx).await?;
+                    size = filled(&ctx, file.get_ref().metadata().await?.len());
+                    unflushed = false;
+                    target = Some(file);
+                }
+                Action::Tee(tee) => ctx.tee = tee,
+                Action::Format(format) => {
+                    ctx.tee_format = Some(format.clone());
+                    ctx.file_format = Some(format);
+                }
+                Action::Rotation(size, count) => {
+                    ctx.size = size;
+                    ctx.count = count;
+                }
+                // taken by the flight recorder, or no recorder to dump
+                Action::Remember(_) | Action::Dump => {}
+            }
+        }
+
+        if exit {
+            break;
+        }
+
+        // flush every interval, zero flushes after every write
+        if unflushed && ts.elapsed() >= ctx.interval {
+            if let Some(file) = target.as_mut() {
+                file.flush().await?;
+            }
+            ts = std::time::Instant::now();
+            unflushed = false;
+        }
+        // sync every second
+        if dirty && ctx.sync == SyncPolicy::EverySecond && synced.elapsed().as_secs() >= 1 {
+            if let Some(file) = target.as_mut() {
+                sync(file).await?;
+            }
+            synced = std::time::Instant::now();
+            dirty = false;
+        }
+        if low_disk(&ctx) {
+            blocking(|| space(&mut ctx));
+        }
+    }
+
+    if let Some(file) = target.as_mut() {
+        file.flush().await?;
+        if dirty && ctx.sync != SyncPolicy::Never {
+            sync(file).await?;
+        }
+    }
+
+    Ok(())
+}
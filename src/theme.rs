@@ -0,0 +1,185 @@
+//! colors of the default stdout layout for `Log2::theme()`
+
+/// terminal colors, the bright ones are the high intensity variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// the color in bold
+    pub fn bold(self) -> Style {
+        Style::from(self).bold()
+    }
+
+    /// the color as it is
+    pub fn normal(self) -> Style {
+        Style::from(self)
+    }
+}
+
+/// a color and a weight, the default style leaves the text untouched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    color: Option<Color>,
+    bold: bool,
+}
+
+impl Style {
+    /// the same style in bold
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    /// the text wrapped in the escapes of the style, regardless of the
+    /// environment since `ColorChoice` has been resolved already
+    #[cfg(feature = "colored")]
+    pub(crate) fn paint(&self, text: &str) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".into());
+        }
+        if let Some(color) = self.color {
+            codes.push(color.into_colored().to_fg_str());
+        }
+        match codes.is_empty() {
+            true => text.to_string(),
+            false => format!("\x1b[{}m{text}\x1b[0m", codes.join(";")),
+        }
+    }
+
+    /// the text as it is, colors are compiled out
+    #[cfg(not(feature = "colored"))]
+    pub(crate) fn paint(&self, text: &str) -> String {
+        text.to_string()
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Style {
+        Style {
+            color: Some(color),
+            bold: false,
+        }
+    }
+}
+
+#[cfg(feature = "colored")]
+impl Color {
+    fn into_colored(self) -> colored::Color {
+        use colored::Color as C;
+        match self {
+            Color::Black => C::Black,
+            Color::Red => C::Red,
+            Color::Green => C::Green,
+            Color::Yellow => C::Yellow,
+            Color::Blue => C::Blue,
+            Color::Magenta => C::Magenta,
+            Color::Cyan => C::Cyan,
+            Color::White => C::White,
+            Color::BrightBlack => C::BrightBlack,
+            Color::BrightRed => C::BrightRed,
+            Color::BrightGreen => C::BrightGreen,
+            Color::BrightYellow => C::BrightYellow,
+            Color::BrightBlue => C::BrightBlue,
+            Color::BrightMagenta => C::BrightMagenta,
+            Color::BrightCyan => C::BrightCyan,
+            Color::BrightWhite => C::BrightWhite,
+            Color::Rgb(r, g, b) => C::TrueColor { r, g, b },
+        }
+    }
+}
+
+/// the palette of the default stdout layout, the file is never colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub error: Style,
+    pub warn: Style,
+    pub info: Style,
+    pub debug: Style,
+    pub trace: Style,
+    /// the square brackets around the time and the level
+    pub bracket: Style,
+    pub time: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            error: Color::BrightRed.normal(),
+            warn: Color::Yellow.normal(),
+            info: Color::Green.normal(),
+            debug: Color::BrightBlue.normal(),
+            trace: Color::Cyan.normal(),
+            bracket: Color::Rgb(0x87, 0x87, 0x87).normal(),
+            time: Style::default(),
+        }
+    }
+}
+
+impl Theme {
+    /// no colors at all
+    pub(crate) fn plain() -> Theme {
+        Theme {
+            error: Style::default(),
+            warn: Style::default(),
+            info: Style::default(),
+            debug: Style::default(),
+            trace: Style::default(),
+            bracket: Style::default(),
+            time: Style::default(),
+        }
+    }
+}
+
+/// when stdout is colored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// colored on a terminal, unless `NO_COLOR` is set or `CLICOLOR` is 0,
+    /// `CLICOLOR_FORCE` or `FORCE_COLOR` color a pipe too, e.g. on CI
+    #[default]
+    Auto,
+    /// colored even when stdout is not a terminal
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub(crate) fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let var = |name| std::env::var(name).ok();
+                let forced = |name| var(name).is_some_and(|v| !v.is_empty() && v != "0");
+                if forced("CLICOLOR_FORCE") || forced("FORCE_COLOR") {
+                    return true;
+                }
+                if var("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+                    return false;
+                }
+                if var("CLICOLOR").is_some_and(|v| v == "0") {
+                    return false;
+                }
+                std::io::IsTerminal::is_terminal(&std::io::stdout())
+            }
+        }
+    }
+}
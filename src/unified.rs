@@ -0,0 +1,35 @@
+//! the unified logging of apple for `Log2::os_log()`, shown by Console.app
+
+use log::Level;
+use oslog::OsLog;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// a log per module, os_log keeps them for the life of the process anyway
+static LOGS: Mutex<BTreeMap<String, Arc<OsLog>>> = Mutex::new(BTreeMap::new());
+
+/// the crate is the subsystem and the rest of the module path the category
+fn split(module: &str) -> (&str, &str) {
+    match module.split_once("::") {
+        Some((subsystem, category)) => (subsystem, category),
+        None => (module, "default"),
+    }
+}
+
+pub(crate) fn log(level: Level, module: &str, message: &str) {
+    let log = {
+        let mut logs = LOGS.lock().unwrap_or_else(|e| e.into_inner());
+        let log = logs.entry(module.to_string()).or_insert_with(|| {
+            let (subsystem, category) = split(module);
+            Arc::new(OsLog::new(subsystem, category))
+        });
+        log.clone()
+    };
+    let level = match level {
+        Level::Error => oslog::Level::Error,
+        Level::Warn => oslog::Level::Default,
+        Level::Info => oslog::Level::Info,
+        Level::Debug | Level::Trace => oslog::Level::Debug,
+    };
+    log.with_level(level, message);
+}
@@ -0,0 +1,109 @@
+//! io_uring appender, a write is submitted without waiting for it and reaped
+//! right before the next one, so the kernel works while the worker formats
+
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+
+pub(crate) struct Uring {
+    ring: io_uring::IoUring,
+    file: std::fs::File,
+    /// the write in flight, its buffer lives until the kernel is done with it
+    inflight: Option<Vec<u8>>,
+    spare: Vec<u8>,
+}
+
+impl Uring {
+    /// hand the file back if the ring can't be set up, e.g. on old kernels
+    pub(crate) fn new(file: std::fs::File) -> Result<Self, std::fs::File> {
+        match io_uring::IoUring::new(8) {
+            Ok(ring) => Ok(Self {
+                ring,
+                file,
+                inflight: None,
+                spare: Vec::new(),
+            }),
+            Err(_) => Err(file),
+        }
+    }
+
+    /// wait for the write in flight, finish it when the kernel wrote it partially
+    fn wait(&mut self) -> Result<(), std::io::Error> {
+        let Some(mut buf) = self.inflight.take() else {
+            return Ok(());
+        };
+        self.ring.submit_and_wait(1)?;
+        let cqe = self
+            .ring
+            .completion()
+            .next()
+            .ok_or_else(|| std::io::Error::other("io_uring completion is missing"))?;
+        let n = cqe.result();
+        let result = if n < 0 {
+            Err(std::io::Error::from_raw_os_error(-n))
+        } else if (n as usize) < buf.len() {
+            self.file.write_all(&buf[n as usize..])
+        } else {
+            Ok(())
+        };
+        buf.clear();
+        self.spare = buf;
+        result
+    }
+
+    fn submit(&mut self, buf: Vec<u8>) -> Result<(), std::io::Error> {
+        if buf.is_empty() {
+            self.spare = buf;
+            return Ok(());
+        }
+        let fd = io_uring::types::Fd(self.file.as_raw_fd());
+        // offset -1 writes at the current position of the append-only file
+        let entry = io_uring::opcode::Write::new(fd, buf.as_ptr(), buf.len() as u32)
+            .offset(u64::MAX)
+            .build();
+        unsafe {
+            self.ring
+                .submission()
+                .push(&entry)
+                .map_err(|_| std::io::Error::other("io_uring submission queue is full"))?;
+        }
+        self.ring.submit()?;
+        self.inflight = Some(buf);
+        Ok(())
+    }
+
+    pub(crate) fn sync_data(&mut self) -> Result<(), std::io::Error> {
+        self.wait()?;
+        self.file.sync_data()
+    }
+}
+
+impl Write for Uring {
+    fn write(&mut self, data: &[u8]) -> Result<usize, std::io::Error> {
+        self.wait()?;
+        let mut buf = std::mem::take(&mut self.spare);
+        buf.extend_from_slice(data);
+        self.submit(buf)?;
+        Ok(data.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize, std::io::Error> {
+        self.wait()?;
+        let mut buf = std::mem::take(&mut self.spare);
+        for data in bufs {
+            buf.extend_from_slice(data);
+        }
+        let n = buf.len();
+        self.submit(buf)?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.wait()
+    }
+}
+
+impl Drop for Uring {
+    fn drop(&mut self) {
+        let _ = self.wait();
+    }
+}
@@ -0,0 +1,46 @@
+use log2::*;
+
+#[test]
+fn access() {
+    let dir = std::env::temp_dir().join("log2_access");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("access.log");
+
+    let log2 = log2::open(dir.join("app.log")).start();
+    let access = log2::access(&path).unwrap();
+    info!("server was started");
+    access
+        .write(&Access {
+            remote: "10.0.0.7".into(),
+            user: "alice".into(),
+            method: "GET".into(),
+            path: "/orders?id=\"1\"".into(),
+            protocol: "HTTP/1.1".into(),
+            status: 200,
+            bytes: 2326,
+            referer: "https://example.com/".into(),
+            user_agent: "curl/8.0".into(),
+            time: None,
+        })
+        .unwrap();
+    access
+        .write(&Access {
+            remote: "10.0.0.8".into(),
+            status: 408,
+            ..Default::default()
+        })
+        .unwrap();
+    log2.flush();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].starts_with("10.0.0.7 - alice ["));
+    assert!(lines[0].ends_with(
+        "] \"GET /orders?id=\\\"1\\\" HTTP/1.1\" 200 2326 \"https://example.com/\" \"curl/8.0\""
+    ));
+    assert!(lines[1].starts_with("10.0.0.8 - - ["));
+    assert!(lines[1].ends_with("] \"-\" 408 - \"-\" \"-\""));
+    let app = std::fs::read_to_string(dir.join("app.log")).unwrap();
+    assert!(app.contains("server was started"));
+    assert!(!app.contains("10.0.0.7"));
+}
@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn buffered_writes() {
+    let path = std::env::temp_dir().join("log2_buffer.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .buffer(1024 * 1024)
+        .flush_interval(std::time::Duration::from_secs(3600))
+        .start();
+
+    info!("order was executed");
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.is_empty());
+
+    log2.flush();
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("order was executed"));
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "bundle")]
+
+use log2::*;
+
+#[test]
+fn bundle() {
+    let dir = std::env::temp_dir().join("log2_bundle");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("log.txt");
+
+    // rotated files of a previous run, 40 days old
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(40 * 24 * 3600);
+    for name in ["log.1.txt", "log.2.txt"] {
+        let file = std::fs::File::create(dir.join(name)).unwrap();
+        file.set_modified(old).unwrap();
+    }
+
+    let mut log2 = log2::open(&path).size(100).rotate(5).bundle(30).start();
+    for i in 0..3 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    log2.stop();
+
+    assert!(dir.join("log.1.txt").exists());
+    for name in ["log.2.txt", "log.3.txt"] {
+        assert!(!dir.join(name).exists());
+    }
+    let tarballs: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with("log-") && name.ends_with(".tar.gz"))
+        .collect();
+    assert_eq!(tarballs.len(), 1);
+    let data = std::fs::read(dir.join(&tarballs[0])).unwrap();
+    assert!(data.starts_with(&[0x1f, 0x8b]));
+}
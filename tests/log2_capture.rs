@@ -0,0 +1,24 @@
+use log2::*;
+
+#[test]
+fn capture() {
+    let _log2 = log2::capture().start();
+
+    info!("order 1 was executed");
+    warn!("order 2 was rejected");
+    error!("order 3 failed");
+
+    // no flush, no sleep
+    let records = captured();
+    assert_eq!(records.len(), 3);
+    assert!(records.contains("order 1 was executed"));
+    assert_eq!(
+        records.level(Level::Warn).messages(),
+        ["order 2 was rejected"]
+    );
+    assert_eq!(records.module("log2_capture").len(), 3);
+    assert!(records.module("log2").is_empty());
+
+    clear_captured();
+    assert!(captured().is_empty());
+}
@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn cef() {
+    let path = std::env::temp_dir().join("log2_cef.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let cef = Cef::new("Acme", "Gateway", "1.0")
+        .field("user", "suser")
+        .field("ip", "src");
+    let log2 = log2::open(&path).format(cef).start();
+    warn!("login failed user=alice ip=10.0.0.7 attempts=3");
+    error!("rule a|b matched");
+    log2.flush();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].ends_with(
+        " CEF:0|Acme|Gateway|1.0|log2_cef|login failed attempts=3|5|suser=alice src=10.0.0.7"
+    ));
+    assert!(lines[1].ends_with(" CEF:0|Acme|Gateway|1.0|log2_cef|rule a\\|b matched|8|"));
+}
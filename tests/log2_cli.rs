@@ -0,0 +1,33 @@
+#![cfg(feature = "cli")]
+
+use clap::Parser;
+use log2::*;
+
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    log: log2::cli::Args,
+}
+
+#[test]
+fn cli() {
+    let path = std::env::temp_dir().join("log2_cli.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let cli = Cli::parse_from(["app", "-vv"]);
+    assert_eq!(cli.log.level(), "trace");
+    let cli = Cli::parse_from(["app", "-q", "--log-level", "warn"]);
+    assert_eq!(cli.log.level(), "warn");
+    assert!(Cli::try_parse_from(["app", "-q", "-v"]).is_err());
+
+    let cli = Cli::parse_from(["app", "--log-file", path, "--log-rotate", "3", "-q"]);
+    let log2 = cli.log.log2().start();
+    warn!("order 1 was rejected");
+    error!("order 2 failed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.contains("order 2 failed"));
+}
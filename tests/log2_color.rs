@@ -0,0 +1,20 @@
+use log2::*;
+
+#[test]
+fn color() {
+    let path = std::env::temp_dir().join("log2_color.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .tee(true)
+        .color(ColorChoice::Always)
+        .start();
+
+    warn!("order 1 was delayed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("[WARN] [log2_color] order 1 was delayed"));
+    assert!(!content.contains('\x1b'));
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "gzip")]
+
+use log2::*;
+
+#[test]
+fn compress() {
+    let dir = std::env::temp_dir().join("log2_compress");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let mut log2 = log2::open(&path)
+        .size(1024)
+        .rotate(3)
+        .compress(true)
+        .start();
+    for i in 0..60 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    let segments = log2.segments();
+    // waits for the compression
+    log2.stop();
+
+    for name in ["log.1.txt", "log.2.txt"] {
+        assert!(!dir.join(name).exists());
+        let data = std::fs::read(dir.join(format!("{name}.gz"))).unwrap();
+        assert!(data.starts_with(&[0x1f, 0x8b]));
+        assert!(data.len() < 1024);
+    }
+    assert!(!dir.join("log.3.txt.gz").exists());
+    assert_eq!(segments.len(), 3);
+}
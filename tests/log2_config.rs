@@ -0,0 +1,39 @@
+#![cfg(feature = "config")]
+
+use log2::*;
+
+#[test]
+fn config() {
+    let dir = std::env::temp_dir().join("log2_config");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("log.txt");
+    let path = path.to_str().unwrap();
+
+    // yaml file
+    let yaml = dir.join("log2.yaml");
+    let text = format!("path: {path}\nlevel: warn\nmodule: false\nmodules: [log2_config]\n");
+    std::fs::write(&yaml, text).unwrap();
+
+    let log2 = log2::from_config(yaml.to_str().unwrap()).unwrap().start();
+    info!("order 1 was executed");
+    warn!("order 2 was rejected");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.ends_with("[WARN] order 2 was rejected\n"));
+
+    // toml text
+    let text = format!("path = '{path}'\nsize = 250\nrotate = 3\nformat = 'json'\n");
+    let log2 = log2::from_config(&text).unwrap().start();
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    assert_eq!(log2.segments().len(), 3);
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.starts_with("{\"time\":"));
+
+    assert!(log2::from_config("colour = 'red'").is_err());
+}
@@ -0,0 +1,25 @@
+#![cfg(all(feature = "config", feature = "lz4"))]
+
+use log2::*;
+
+#[test]
+fn config_compress() {
+    let dir = std::env::temp_dir().join("log2_config_compress");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let path = path.to_str().unwrap();
+
+    let text =
+        format!("path = '{path}'\nsize = 1024\nrotate = 3\ncompress = true\ncodec = 'lz4'\n");
+    let mut log2 = log2::from_config(&text).unwrap().start();
+    for i in 0..60 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    log2.stop();
+
+    for name in ["log.1.txt", "log.2.txt"] {
+        assert!(!dir.join(name).exists());
+        assert!(dir.join(format!("{name}.lz4")).exists());
+    }
+}
@@ -0,0 +1,28 @@
+use log2::*;
+
+#[test]
+fn continuation() {
+    let path = std::env::temp_dir().join("log2_continuation.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .module(false)
+        .continuation(Continuation::Align)
+        .start();
+
+    info!("order 1 was executed\nprice: 10\nquantity: 2");
+    log2.set_format(|line: &mut String, parts: &Parts| {
+        *line += &format!("{} {}", parts.level, parts.message);
+    });
+    info!("order 2 was executed\nprice: 20");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    let column = lines[0].find("order 1").unwrap();
+    assert_eq!(lines[1], format!("{}price: 10", " ".repeat(column)));
+    assert_eq!(lines[2], format!("{}quantity: 2", " ".repeat(column)));
+    assert_eq!(lines[3], "INFO order 2 was executed");
+    assert_eq!(lines[4], "     price: 20");
+}
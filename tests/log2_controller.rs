@@ -0,0 +1,29 @@
+use log2::*;
+
+#[test]
+fn controller() {
+    let path = std::env::temp_dir().join("log2_controller.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let mut log2 = log2::open(&path).start();
+    let controller = log2.downgrade();
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let controller = controller.clone();
+            std::thread::spawn(move || {
+                info!("order {i} was executed");
+                controller.flush();
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    for i in 0..4 {
+        assert!(text.contains(&format!("order {i} was executed")));
+    }
+    assert_eq!(controller.stats().info, 4);
+    log2.stop();
+}
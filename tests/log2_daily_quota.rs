@@ -0,0 +1,27 @@
+use log2::*;
+
+#[test]
+fn daily_quota() {
+    let path = std::env::temp_dir().join("log2_daily_quota.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let mut log2 = log2::open(&path).daily_quota(1024).start();
+    for i in 0..100 {
+        info!("order {i} was executed");
+    }
+    // the note of the quota is queued behind the records
+    log2.flush();
+    error!("order 100 failed");
+    log2.stop();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("order 0 was executed"));
+    assert!(!text.contains("order 99 was executed"));
+    assert_eq!(
+        text.matches("the daily quota of 1024 bytes is used up")
+            .count(),
+        1
+    );
+    assert!(text.contains("order 100 failed"));
+    assert!(text.len() < 2048);
+}
@@ -0,0 +1,47 @@
+use log2::*;
+
+mod sqlx {
+    pub mod query {
+        pub fn run() {
+            log2::info!("order 2 was queried");
+        }
+    }
+}
+
+mod h2 {
+    pub mod frame {
+        pub fn send() {
+            log2::info!("order 3 was framed");
+        }
+    }
+}
+
+mod app {
+    pub fn run() {
+        log2::info!("order 4 was executed");
+    }
+}
+
+#[test]
+fn deny_modules() {
+    let path = std::env::temp_dir().join("log2_deny_modules.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .allow_modules(["log2_deny_modules*"])
+        .deny_modules(["*::sqlx::query", "*::h2::*"])
+        .start();
+
+    info!("order 1 was placed");
+    sqlx::query::run();
+    h2::frame::send();
+    app::run();
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("order 1 was placed"));
+    assert!(!content.contains("order 2"));
+    assert!(!content.contains("order 3"));
+    assert!(content.contains("[log2_deny_modules::app] order 4 was executed"));
+}
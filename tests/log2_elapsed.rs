@@ -0,0 +1,33 @@
+use log2::*;
+
+#[test]
+fn elapsed() {
+    let path = std::env::temp_dir().join("log2_elapsed.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .module(false)
+        .elapsed(true)
+        .delta(true)
+        .start();
+    info!("order 1 was executed");
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    info!("order 2 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let columns: Vec<Vec<f64>> = content
+        .lines()
+        .map(|line| {
+            line.split("] [")
+                .skip(1)
+                .take(2)
+                .map(|c| c.trim_start_matches('+').parse().unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(columns[0][1], 0.0);
+    assert!(columns[1][0] >= columns[0][0] + 0.1);
+    assert!(columns[1][1] >= 0.1);
+}
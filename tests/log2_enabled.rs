@@ -0,0 +1,22 @@
+use log::log_enabled;
+use log2::*;
+
+#[test]
+fn enabled() {
+    let path = std::env::temp_dir().join("log2_enabled.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let mut log2 = log2::open(&path)
+        .level("info")
+        .deny_modules(["sqlx"])
+        .start();
+    assert_eq!(log2.level(), level::Info);
+    assert!(log_enabled!(Level::Info));
+    assert!(!log_enabled!(Level::Debug));
+    assert!(!log_enabled!(target: "sqlx::query", Level::Error));
+
+    log2.set_level("warn");
+    assert_eq!(log2.level(), level::Warn);
+    assert!(!log_enabled!(Level::Info));
+    log2.stop();
+}
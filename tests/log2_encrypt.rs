@@ -0,0 +1,49 @@
+#![cfg(feature = "encrypt")]
+
+use log2::*;
+
+#[test]
+fn encrypt_archives() {
+    let dir = std::env::temp_dir().join("log2_encrypt");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let key = [7; 32];
+
+    let mut log2 = log2::open(&path)
+        .size(1024)
+        .rotate(3)
+        .encrypt_archives(key)
+        .start();
+    for i in 0..30 {
+        info!("order {i} was executed");
+    }
+    warn!("no more orders");
+    log2.flush();
+    log2.stop();
+
+    assert!(!dir.join("log.1.txt").exists());
+    let archive = dir.join("log.1.txt.enc");
+    let content = decrypt(&archive, &key).unwrap();
+    let content = String::from_utf8(content).unwrap();
+    assert!(content.contains("was executed"));
+    assert!(decrypt(&archive, &[8; 32]).is_err());
+    let active = std::fs::read_to_string(&path).unwrap();
+    assert!(active.contains("no more orders"));
+
+    // compressed first
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut log2 = log2::open(&path)
+        .size(1024)
+        .rotate(3)
+        .compress(true)
+        .encrypt_archives(key)
+        .start();
+    for i in 0..30 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    log2.stop();
+
+    let content = decrypt(dir.join("log.1.txt.gz.enc"), &key).unwrap();
+    assert!(content.starts_with(&[0x1f, 0x8b]));
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+#[test]
+fn enrich_record() {
+    let path = std::env::temp_dir().join("log2_enrich.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let mut log2 = log2::open(path)
+        .enrich(|_: &Record| vec![("build".to_string(), "42".to_string())])
+        .enrich(|record: &Record| {
+            vec![("line".to_string(), record.line().unwrap_or(0).to_string())]
+        })
+        .start();
+
+    info!("order was executed");
+    log2.stop();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("order was executed build=42 line="));
+}
@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn env() {
+    let path = std::env::temp_dir().join("log2_env.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    std::env::set_var("LOG2_FILE", path);
+    std::env::set_var("LOG2_LEVEL", "warn");
+    std::env::set_var("LOG2_TEE", "off");
+    std::env::set_var("LOG2_ROTATE", "oops");
+
+    let log2 = log2::stdout().level("trace").start();
+    info!("order 1 was executed");
+    warn!("order 2 was rejected");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.contains("order 2 was rejected"));
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+#[test]
+fn env_start() {
+    let path = std::env::temp_dir().join("log2_env_start.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    std::env::set_var("LOG2_FILE", path);
+    std::env::set_var("LOG2_LEVEL", "error");
+    std::env::set_var("LOG2_TEE", "off");
+
+    let log2 = log2::start();
+    info!("order 1 was executed");
+    error!("order 2 failed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.contains("order 2 failed"));
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+#[test]
+fn escape() {
+    let path = std::env::temp_dir().join("log2_escape.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).module(false).start();
+
+    let user = "bob\n[2023-06-01 10:00:00.000] [INFO] admin logged in\r\x1b[2J";
+    info!("order 1 was placed by {user}");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].ends_with(
+        "[INFO] order 1 was placed by bob\\n[2023-06-01 10:00:00.000] [INFO] admin logged in\\r\\x1b[2J"
+    ));
+}
@@ -0,0 +1,27 @@
+use log2::*;
+
+// the test runs itself again as a child process which leaks the handle and exits
+#[test]
+fn flush_on_exit() {
+    let path = std::env::temp_dir().join("log2_exit.txt");
+
+    if std::env::var("LOG2_EXIT_CHILD").is_ok() {
+        let log2 = log2::open(path.to_str().unwrap()).start();
+        for i in 0..1000 {
+            info!("order {i} was executed");
+        }
+        std::mem::forget(log2);
+        return;
+    }
+
+    let _ = std::fs::remove_file(&path);
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["flush_on_exit", "--exact"])
+        .env("LOG2_EXIT_CHILD", "1")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.contains("order 999 was executed"));
+}
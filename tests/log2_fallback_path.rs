@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn fallback_path() {
+    let dir = std::env::temp_dir().join("log2_fallback_path");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    // a file where the directory should be, even root can't create it
+    std::fs::write(dir.join("blocked"), "").unwrap();
+    let fallback = dir.join("fallback.txt");
+
+    let mut log2 = log2::open(dir.join("blocked/log.txt"))
+        .fallback_path(&fallback)
+        .start();
+    info!("order was executed");
+    log2.stop();
+
+    let text = std::fs::read_to_string(&fallback).unwrap();
+    assert!(text.contains("order was executed"));
+    assert!(text.contains("[WARN]"));
+    assert!(text.contains("logging to"));
+}
@@ -0,0 +1,27 @@
+use log2::*;
+
+// the test runs itself again as a child process which exits through fatal!
+#[test]
+fn fatal() {
+    let path = std::env::temp_dir().join("log2_fatal.txt");
+
+    if std::env::var("LOG2_FATAL_CHILD").is_ok() {
+        let _log2 = log2::open(path.to_str().unwrap()).fatal_code(3).start();
+        for i in 0..1000 {
+            info!("order {i} was executed");
+        }
+        fatal!("unable to load the config");
+    }
+
+    let _ = std::fs::remove_file(&path);
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["fatal", "--exact"])
+        .env("LOG2_FATAL_CHILD", "1")
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.contains("order 999 was executed"));
+    assert!(content.ends_with("[ERROR] [log2_fatal] unable to load the config\n"));
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "tokio")]
+use log2::*;
+
+// the test runs itself again as a child process which exits through fatal!
+#[test]
+fn fatal_tokio() {
+    let path = std::env::temp_dir().join("log2_fatal_tokio.txt");
+
+    if std::env::var("LOG2_FATAL_CHILD").is_ok() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let _log2 = log2::open(path.to_str().unwrap())
+                .fatal_code(3)
+                .flush_interval(std::time::Duration::from_secs(3600))
+                .start_tokio();
+            for i in 0..1000 {
+                info!("order {i} was executed");
+            }
+            fatal!("the exchange is gone");
+        });
+        return;
+    }
+
+    let _ = std::fs::remove_file(&path);
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["fatal_tokio", "--exact"])
+        .env("LOG2_FATAL_CHILD", "1")
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(3));
+
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.contains("order 999 was executed"));
+    assert!(content.ends_with("[ERROR] [log2_fatal_tokio] the exchange is gone\n"));
+}
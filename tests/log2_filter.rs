@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn filter() {
+    let path = std::env::temp_dir().join("log2_filter.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .filter(|record| record.target() != "ureq" || record.level() <= Level::Warn)
+        .start();
+
+    debug!(target: "ureq", "order 1 was sent");
+    warn!(target: "ureq", "order 2 was retried");
+    debug!("order 3 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(!content.contains("order 1"));
+    assert!(content.contains("order 2 was retried"));
+    assert!(content.contains("order 3 was executed"));
+}
@@ -0,0 +1,38 @@
+use log2::*;
+
+#[test]
+fn flight_recorder() {
+    let path = std::env::temp_dir().join("log2_flight_recorder.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .module(false)
+        .level("info")
+        .flight_recorder(3)
+        .start();
+
+    for i in 0..10 {
+        trace!("order {i} was traced");
+    }
+    info!("order 10 was executed");
+    error!("order 11 failed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].contains("order 10 was executed"));
+    assert!(lines[1].contains("[TRACE] order 7 was traced"));
+    assert!(lines[3].contains("[TRACE] order 9 was traced"));
+    assert!(lines[4].contains("[ERROR] order 11 failed"));
+
+    // on demand
+    debug!("order 12 was debugged");
+    log2.dump();
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.ends_with("[DEBUG] order 12 was debugged\n"));
+    assert_eq!(log2.queue_len(), 0);
+}
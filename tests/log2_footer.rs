@@ -0,0 +1,32 @@
+use log2::*;
+
+#[test]
+fn footer() {
+    let dir = std::env::temp_dir().join("log2_footer");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path)
+        .size(100)
+        .rotate(2)
+        .footer(|trailer| {
+            format!(
+                "=== {} bytes, {} records ===",
+                trailer.bytes, trailer.records
+            )
+        })
+        .start();
+
+    info!("order 1 was executed");
+    info!("order 2 was executed");
+    info!("order 3 was executed");
+    log2.flush();
+
+    let segments = log2.segments();
+    let rotated = std::fs::read_to_string(&segments[1].path).unwrap();
+    let lines: Vec<&str> = rotated.lines().collect();
+    let bytes = rotated.len() - lines[2].len() - 1;
+    assert_eq!(lines[2], format!("=== {bytes} bytes, 2 records ==="));
+    let active = std::fs::read_to_string(&segments[0].path).unwrap();
+    assert!(!active.contains("==="));
+}
@@ -0,0 +1,32 @@
+use log2::*;
+
+#[test]
+fn format() {
+    let path = std::env::temp_dir().join("log2_format.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let compact = |line: &mut String, parts: &Parts| {
+        line.push_str(parts.level.as_str());
+        line.push(' ');
+        line.push_str(parts.message);
+    };
+    let verbose = |line: &mut String, parts: &Parts| {
+        *line += &format!(
+            "{} {} {}: {}",
+            parts.time, parts.level, parts.module, parts.message
+        );
+    };
+
+    let log2 = log2::open(path).format(compact).start();
+
+    info!("order 1 was executed");
+    log2.set_format(verbose);
+    info!("order 2 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], "INFO order 1 was executed");
+    assert!(lines[1].ends_with(" INFO log2_format: order 2 was executed"));
+}
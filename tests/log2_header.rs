@@ -0,0 +1,25 @@
+use log2::*;
+
+#[test]
+fn header() {
+    let dir = std::env::temp_dir().join("log2_header");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path)
+        .size(100)
+        .rotate(3)
+        .header(|| format!("=== app v{} started ===", "1.0.0"))
+        .start();
+
+    for i in 0..4 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    for segment in log2.segments() {
+        let content = std::fs::read_to_string(&segment.path).unwrap();
+        assert!(content.starts_with("=== app v1.0.0 started ===\n"));
+    }
+    assert_eq!(log2.segments().len(), 3);
+}
@@ -0,0 +1,26 @@
+use log2::*;
+
+#[test]
+fn healthy() {
+    let path = std::env::temp_dir().join("log2_healthy.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let mut log2 = log2::open(path).start();
+
+    for i in 0..100 {
+        info!("order {i} was executed");
+    }
+    assert!(log2.queue_len() <= 100);
+    log2.flush();
+    assert_eq!(log2.queue_len(), 0);
+    assert!(log2.healthy());
+
+    // the probe doesn't bring a removed file back
+    std::fs::remove_file(path).unwrap();
+    assert!(!log2.healthy());
+    assert!(!std::path::Path::new(path).exists());
+
+    log2.stop();
+    assert!(!log2.healthy());
+}
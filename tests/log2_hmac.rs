@@ -0,0 +1,34 @@
+#![cfg(feature = "hmac")]
+
+use log2::*;
+
+#[test]
+fn hmac() {
+    let dir = std::env::temp_dir().join("log2_hmac");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let key = b"secret";
+
+    for run in 0..2 {
+        let mut log2 = log2::open(&path)
+            .header(|| "# orders".into())
+            .hmac(key)
+            .start();
+        info!("order {run} was executed");
+        warn!("order {run} was delayed");
+        log2.stop();
+    }
+    assert_eq!(log2::verify(&path, key).unwrap(), 6);
+    assert!(log2::verify(&path, b"other").is_err());
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let edited = content.replace("order 1 was delayed", "order 1 was on time");
+    std::fs::write(&path, edited).unwrap();
+    let e = log2::verify(&path, key).unwrap_err();
+    assert!(e.to_string().contains("line 6"));
+
+    let mut lines: Vec<_> = content.lines().collect();
+    lines.remove(2);
+    std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+    assert!(log2::verify(&path, key).is_err());
+}
@@ -0,0 +1,25 @@
+use log2::*;
+
+#[test]
+fn internal_diagnostics() {
+    let dir = std::env::temp_dir().join("log2_internal");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("log.txt");
+    let path = path.to_str().unwrap();
+
+    // a directory in the way of the first rotated segment
+    std::fs::create_dir_all(dir.join("log.1.txt/blocked")).unwrap();
+
+    let log2 = log2::open(path).size(256).rotate(2).module(true).start();
+
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    // reports raised while flushing are queued behind the first flush
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("[ERROR] [log2::internal] failed to rotate"));
+}
@@ -0,0 +1,23 @@
+use log2::*;
+
+#[test]
+fn label() {
+    let path = std::env::temp_dir().join("log2_label.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .module(false)
+        .label(Level::Warn, "WRN")
+        .align_levels(true)
+        .start();
+
+    warn!("order 1 was delayed");
+    error!("order 2 was broken");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].ends_with("] [WRN]   order 1 was delayed"));
+    assert!(lines[1].ends_with("] [ERROR] order 2 was broken"));
+}
@@ -0,0 +1,28 @@
+use log2::*;
+
+#[test]
+fn layer() {
+    let path = std::env::temp_dir().join("log2_layer.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .layer(|view: RecordView| (!view.message.contains("heartbeat")).then_some(view))
+        .layer(|mut view: RecordView| {
+            if view.message.contains("timeout") {
+                view.level = Level::Warn;
+                view.message += " retry=3";
+            }
+            Some(view)
+        })
+        .start();
+
+    info!("order 1 heartbeat");
+    info!("order 2 timeout");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].ends_with("[WARN] [log2_layer] order 2 timeout retry=3"));
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+#[test]
+fn line_ending() {
+    let path = std::env::temp_dir().join("log2_line_ending.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .module(false)
+        .line_ending(LineEnding::CrLf)
+        .start();
+
+    info!("order 1 was executed");
+    info!("order 2 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content.matches("\r\n").count(), 2);
+    assert!(content.ends_with("[INFO] order 2 was executed\r\n"));
+}
@@ -0,0 +1,27 @@
+#![cfg(unix)]
+
+use log2::*;
+
+#[test]
+fn link_latest() {
+    let dir = std::env::temp_dir().join("log2_link_latest");
+    let _ = std::fs::remove_dir_all(&dir);
+    let latest = dir.join("current.log");
+
+    let mut log2 = log2::open(dir.join("a.log")).link_latest(&latest).start();
+
+    info!("order 1 was executed");
+    log2.flush();
+    assert_eq!(std::fs::read_link(&latest).unwrap(), dir.join("a.log"));
+    let content = std::fs::read_to_string(&latest).unwrap();
+    assert!(content.contains("order 1 was executed"));
+
+    log2.redirect(dir.join("b.log"));
+    info!("order 2 was executed");
+    log2.flush();
+
+    assert_eq!(std::fs::read_link(&latest).unwrap(), dir.join("b.log"));
+    let content = std::fs::read_to_string(&latest).unwrap();
+    assert!(!content.contains("order 1"));
+    assert!(content.contains("order 2 was executed"));
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+#[test]
+fn logfmt() {
+    let path = std::env::temp_dir().join("log2_logfmt.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let log2 = log2::open(&path).logfmt().start();
+    info!("order \"1\" was executed");
+    warn!("limit=5");
+    log2.flush();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].starts_with("ts="));
+    assert!(!lines[0][3..].starts_with('"'));
+    assert!(
+        lines[0].ends_with(" level=info module=log2_logfmt msg=\"order \\\"1\\\" was executed\"")
+    );
+    assert!(lines[1].ends_with(" level=warn module=log2_logfmt msg=\"limit=5\""));
+}
@@ -0,0 +1,31 @@
+use log2::*;
+
+#[test]
+fn logger() {
+    let path = std::env::temp_dir().join("log2_logger.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let mut log2 = log2::open(&path).level("info").start();
+    let db = log2::logger("db");
+    let pool = db.child("pool");
+    let http = log2::logger("http");
+    db.set_level("debug");
+    assert_eq!(pool.name(), "db.pool");
+    assert_eq!(pool.level(), level::Debug);
+    assert_eq!(http.level(), level::Info);
+
+    pool.debug(format_args!("order 1 got a connection"));
+    http.debug(format_args!("order 2 was requested"));
+    http.info(format_args!("order 3 was requested"));
+    debug!("order 4 was executed");
+    pool.set_level("warn");
+    pool.info(format_args!("order 5 got a connection"));
+    log2.stop();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("[db.pool] order 1 got a connection"));
+    assert!(!text.contains("order 2"));
+    assert!(text.contains("[http] order 3 was requested"));
+    assert!(!text.contains("order 4"));
+    assert!(!text.contains("order 5"));
+}
@@ -0,0 +1,31 @@
+#![cfg(all(feature = "disk", unix))]
+
+use log2::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn low_disk() {
+    let dir = std::env::temp_dir().join("log2_low_disk");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let free = Arc::new(Mutex::new(None));
+    let hook = free.clone();
+    // no disk has that much room
+    let mut log2 = log2::open(&path)
+        .low_disk(u64::MAX)
+        .on_low_disk(move |bytes| *hook.lock().unwrap() = Some(bytes))
+        .start();
+    info!("order was executed");
+    for _ in 0..100 {
+        if free.lock().unwrap().is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    log2.stop();
+
+    assert!(free.lock().unwrap().is_some_and(|bytes| bytes > 0));
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.matches("low disk space").count(), 1);
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "lz4")]
+
+use log2::*;
+
+#[test]
+fn lz4() {
+    let dir = std::env::temp_dir().join("log2_lz4");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let mut log2 = log2::open(&path)
+        .size(1024)
+        .rotate(3)
+        .compress(true)
+        .codec(Codec::Lz4)
+        .start();
+    for i in 0..60 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    log2.stop();
+
+    for name in ["log.1.txt", "log.2.txt"] {
+        assert!(!dir.join(name).exists());
+        let data = std::fs::read(dir.join(format!("{name}.lz4"))).unwrap();
+        assert!(data.starts_with(&[0x04, 0x22, 0x4d, 0x18]));
+        assert!(data.len() < 1024);
+    }
+}
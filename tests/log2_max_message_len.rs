@@ -0,0 +1,20 @@
+use log2::*;
+
+#[test]
+fn max_message_len() {
+    let path = std::env::temp_dir().join("log2_max_message_len.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).max_message_len(20).start();
+
+    let payload = "x".repeat(1000);
+    info!("order 1 was executed");
+    info!("order 2 payload {payload}");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].ends_with(" order 1 was executed"));
+    assert!(lines[1].ends_with(" order 2 payload xxxx…[truncated 996 bytes]"));
+}
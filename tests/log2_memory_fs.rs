@@ -0,0 +1,38 @@
+#![cfg(feature = "memory-fs")]
+
+use log2::*;
+use std::path::PathBuf;
+
+#[test]
+fn memory_fs() {
+    let dir = std::env::temp_dir().join("log2_memory_fs");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let fs = MemoryFs::new();
+    fs.write(&path, "order 0 of the previous run\n");
+
+    let log2 = log2::open(&path)
+        .size(100)
+        .rotate(3)
+        .memory_fs(fs.clone())
+        .start();
+    for i in 1..=21 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let names: Vec<PathBuf> = ["log.1.txt", "log.2.txt", "log.txt"]
+        .iter()
+        .map(|name| dir.join(name))
+        .collect();
+    assert_eq!(fs.files(), names);
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[0].path, path);
+    let active = String::from_utf8(fs.read(&path).unwrap()).unwrap();
+    assert!(active.ends_with("order 21 was executed\n"), "{active}");
+    let oldest = String::from_utf8(fs.read(&names[1]).unwrap()).unwrap();
+    assert!(!oldest.contains("order 0"));
+    assert!(log2.healthy());
+    assert!(!dir.exists());
+}
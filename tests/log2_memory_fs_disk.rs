@@ -0,0 +1,25 @@
+#![cfg(feature = "memory-fs")]
+
+use log2::*;
+
+#[test]
+fn memory_fs_disk() {
+    let dir = std::env::temp_dir().join("log2_memory_fs_disk");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let template = dir.join("%Y-%m-%d.log");
+    let started = std::panic::catch_unwind(|| {
+        log2::template(template.to_str().unwrap())
+            .memory_fs(MemoryFs::new())
+            .start()
+    });
+    assert!(started.is_err());
+    let started = std::panic::catch_unwind(|| {
+        log2::open(dir.join("log.txt"))
+            .shared(true)
+            .memory_fs(MemoryFs::new())
+            .start()
+    });
+    assert!(started.is_err());
+    assert!(!dir.exists());
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "metrics")]
+
+use log2::*;
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Recorded {
+    counters: Mutex<Vec<(Key, Arc<Total>)>>,
+}
+
+#[derive(Default)]
+struct Total(AtomicU64);
+
+impl CounterFn for Total {
+    fn increment(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+impl Recorded {
+    fn total(&self, name: &str, label: Option<&str>) -> u64 {
+        let counters = self.counters.lock().unwrap();
+        counters
+            .iter()
+            .filter(|(key, _)| key.name() == name)
+            .filter(|(key, _)| label.is_none_or(|l| key.labels().any(|x| x.value() == l)))
+            .map(|(_, total)| total.0.load(Ordering::Relaxed))
+            .sum()
+    }
+}
+
+struct Shared(Arc<Recorded>);
+
+impl Recorder for Shared {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.0.counters.lock().unwrap();
+        let total = match counters.iter().find(|(k, _)| k == key) {
+            Some((_, total)) => total.clone(),
+            None => {
+                let total = Arc::new(Total::default());
+                counters.push((key.clone(), total.clone()));
+                total
+            }
+        };
+        Counter::from_arc(total)
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[test]
+fn metrics_recorder() {
+    let recorded = Arc::new(Recorded::default());
+    metrics::set_global_recorder(Shared(recorded.clone())).unwrap();
+
+    let path = std::env::temp_dir().join("log2_metrics.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).size(512).rotate(2).start();
+
+    for i in 0..20 {
+        info!("order {i} was executed");
+    }
+    error!("order 20 failed");
+    log2.flush();
+
+    assert_eq!(recorded.total("log2_records_total", Some("INFO")), 20);
+    assert_eq!(recorded.total("log2_records_total", Some("ERROR")), 1);
+    assert!(recorded.total("log2_rotations_total", None) >= 1);
+    assert_eq!(recorded.total("log2_bytes_total", None), log2.stats().bytes);
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "mmap")]
+use log2::*;
+
+#[test]
+fn mmap_appender() {
+    let dir = std::env::temp_dir().join("log2_mmap");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let path = path.to_str().unwrap();
+
+    let mut log2 = log2::open(path).mmap(true).size(1000).rotate(3).start();
+    for i in 0..60 {
+        info!("order {i} was executed");
+    }
+    log2.stop();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.ends_with("order 59 was executed\n"));
+    // rotated files are trimmed to the records
+    let archived = std::fs::read_to_string(&segments[1].path).unwrap();
+    assert!(!archived.contains('\0'));
+    assert!(segments[1].size >= 1000 && segments[1].size < 1100);
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "mmap")]
+use log2::*;
+
+#[test]
+fn mmap_padding() {
+    let dir = std::env::temp_dir().join("log2_mmap_padding");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("log.txt");
+
+    // a reservation left behind by a crash
+    let mut leftover = b"order 0 was executed\n".to_vec();
+    leftover.resize(1024 * 1024, 0);
+    std::fs::write(&path, leftover).unwrap();
+
+    let mut log2 = log2::open(&path).mmap(true).size(1000).rotate(3).start();
+    info!("order 1 was executed");
+    log2.stop();
+
+    // no rotation, and the file holds the records only
+    assert_eq!(log2.segments().len(), 1);
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.starts_with("order 0 was executed\n"));
+    assert!(content.ends_with("order 1 was executed\n"));
+    assert!(!content.contains('\0'));
+}
@@ -0,0 +1,28 @@
+#![cfg(unix)]
+
+use log2::*;
+use std::os::unix::fs::PermissionsExt;
+
+#[test]
+fn mode() {
+    let dir = std::env::temp_dir().join("log2_mode");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path).size(100).rotate(3).mode(0o600).start();
+
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    for segment in segments {
+        let mode = std::fs::metadata(&segment.path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
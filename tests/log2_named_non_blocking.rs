@@ -0,0 +1,18 @@
+
+#[test]
+fn named_non_blocking() {
+    let path = std::env::temp_dir().join("log2_named_non_blocking.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).non_blocking(8).start();
+
+    log2::logger("billing.invoice").info(format_args!("order 1 was billed"));
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(
+        content.contains("[billing.invoice] order 1 was billed"),
+        "{content}"
+    );
+}
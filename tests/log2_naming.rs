@@ -0,0 +1,28 @@
+use log2::*;
+
+#[test]
+fn naming() {
+    let dir = std::env::temp_dir().join("log2_naming");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path)
+        .size(100)
+        .rotate(3)
+        .naming(Naming::Date)
+        .start();
+
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+    let newest = segments[1].path.file_name().unwrap().to_str().unwrap();
+    assert!(newest.starts_with("log.20") && newest.ends_with(".txt"));
+    assert!(newest.contains('_'));
+    let content = std::fs::read_to_string(&segments[1].path).unwrap();
+    assert!(content.contains("order 9 was executed"));
+}
@@ -0,0 +1,31 @@
+use log2::*;
+
+#[test]
+fn non_blocking_ring() {
+    let path = std::env::temp_dir().join("log2_non_blocking.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).non_blocking(8).start();
+
+    for i in 0..1000 {
+        info!("order {i} was executed");
+    }
+    // room in the ring for the long one
+    log2.flush();
+    error!("x{}", "é".repeat(1000));
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let written = content.lines().count() as u64;
+    assert!(written > 0);
+    assert_eq!(written + log2.dropped(), 1001);
+
+    // long messages are cut to the frame size, between characters
+    let line = content
+        .lines()
+        .find(|line| line.contains("[ERROR]"))
+        .expect("the error record is missing");
+    let message = line.rsplit("] ").next().unwrap();
+    assert_eq!(message, format!("x{}", "é".repeat(255)));
+}
@@ -0,0 +1,39 @@
+use log2::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn on_prune() {
+    let dir = std::env::temp_dir().join("log2_on_prune");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let pruned = Arc::new(Mutex::new(Vec::new()));
+    let paths = pruned.clone();
+    let mut log2 = log2::open(&path)
+        .size(1024)
+        .rotate(3)
+        .on_prune(move |path| {
+            let mut paths = paths.lock().unwrap();
+            paths.push(path.to_path_buf());
+            // the first one is exported and kept
+            match paths.len() {
+                1 => Decision::Keep,
+                _ => Decision::Delete,
+            }
+        })
+        .start();
+    for i in 0..100 {
+        info!("order {i} was executed");
+    }
+    log2.stop();
+
+    let pruned = pruned.lock().unwrap();
+    assert!(pruned.len() >= 2);
+    assert!(pruned.iter().all(|path| *path == dir.join("log.2.txt")));
+    let kept: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with("log.2.") && name != "log.2.txt")
+        .collect();
+    assert_eq!(kept.len(), 1);
+}
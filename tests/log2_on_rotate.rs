@@ -0,0 +1,26 @@
+use log2::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn on_rotate() {
+    let dir = std::env::temp_dir().join("log2_on_rotate");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let rotated = Arc::new(Mutex::new(Vec::new()));
+    let paths = rotated.clone();
+    let mut log2 = log2::open(&path)
+        .size(1024)
+        .rotate(3)
+        .on_rotate(move |path| paths.lock().unwrap().push(path.to_path_buf()))
+        .start();
+    for i in 0..60 {
+        info!("order {i} was executed");
+    }
+    // waits for the callbacks
+    log2.stop();
+
+    let rotated = rotated.lock().unwrap();
+    assert!(rotated.len() >= 2);
+    assert!(rotated.iter().all(|path| *path == dir.join("log.1.txt")));
+}
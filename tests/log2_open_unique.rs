@@ -0,0 +1,19 @@
+use log2::*;
+
+#[test]
+fn open_unique() {
+    let dir = std::env::temp_dir().join("log2_open_unique");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("app.log");
+
+    let log2 = log2::open_unique(&path).start();
+
+    info!("order 1 was executed");
+    log2.flush();
+
+    let unique = dir.join(format!("app.{}.log", std::process::id()));
+    assert_eq!(log2.segments()[0].path, unique);
+    assert!(!path.exists());
+    let content = std::fs::read_to_string(unique).unwrap();
+    assert!(content.contains("order 1 was executed"));
+}
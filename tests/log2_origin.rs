@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn origin() {
+    let path = std::env::temp_dir().join("log2_origin.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).hostname(true).pid(true).thread(true).start();
+    std::thread::Builder::new()
+        .name("matcher".into())
+        .spawn(|| info!("order 1 was executed"))
+        .unwrap()
+        .join()
+        .unwrap();
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains(&format!("[{}] [matcher:", std::process::id())));
+    let columns: Vec<&str> = content.split("] [").collect();
+    assert!(!columns[1].is_empty());
+}
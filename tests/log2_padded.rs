@@ -0,0 +1,24 @@
+use log2::*;
+
+#[test]
+fn padded() {
+    let dir = std::env::temp_dir().join("log2_padded");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path)
+        .size(100)
+        .rotate(11)
+        .naming(Naming::Padded)
+        .start();
+
+    for i in 0..30 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 11);
+    assert_eq!(segments[1].path, dir.join("log.01.txt"));
+    assert_eq!(segments[10].path, dir.join("log.10.txt"));
+}
@@ -0,0 +1,20 @@
+#[test]
+fn capture_panics() {
+    let path = std::env::temp_dir().join("log2_panic.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).capture_panics(true).start();
+
+    let result = std::thread::Builder::new()
+        .name("order".into())
+        .spawn(|| panic!("order book is corrupted"))
+        .unwrap()
+        .join();
+    assert!(result.is_err());
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("[ERROR] [log2] thread 'order' panicked at"));
+    assert!(content.contains("order book is corrupted"));
+}
@@ -0,0 +1,28 @@
+use log2::*;
+
+#[test]
+fn path() {
+    let dir = std::env::temp_dir().join("log2_path");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    #[cfg(unix)]
+    let name = {
+        use std::os::unix::ffi::OsStrExt;
+        std::ffi::OsStr::from_bytes(b"app-\xff.log").to_owned()
+    };
+    #[cfg(not(unix))]
+    let name = std::ffi::OsString::from("app.log");
+
+    let log2 = log2::open(dir.join(&name)).size(100).rotate(3).start();
+
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[0].path, dir.join(&name));
+    let rotated = segments[1].path.file_name().unwrap();
+    assert_eq!(rotated.len(), name.len() + 2);
+}
@@ -0,0 +1,41 @@
+#![cfg(unix)]
+
+use log2::*;
+
+#[test]
+fn pipe_child() {
+    let dir = std::env::temp_dir().join("log2_pipe_child");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path).start();
+    let mut command = std::process::Command::new("sh");
+    command.args(["-c", "echo migrating; echo table locked >&2"]);
+    log2::pipe_child(&mut command, level::Info)
+        .unwrap()
+        .wait()
+        .unwrap();
+    let mut command = std::process::Command::new("sh");
+    command.args(["-c", "echo hidden; echo disk full >&2"]);
+    ChildLogger::new("backup")
+        .level(level::Off)
+        .stderr_level(level::Error)
+        .spawn(&mut command)
+        .unwrap()
+        .wait()
+        .unwrap();
+
+    let mut content = String::new();
+    for _ in 0..100 {
+        log2.flush();
+        content = std::fs::read_to_string(&path).unwrap_or_default();
+        if content.lines().count() == 3 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(content.contains("[INFO] [sh] migrating"), "{content}");
+    assert!(content.contains("[INFO] [sh] table locked"), "{content}");
+    assert!(content.contains("[ERROR] [backup] disk full"), "{content}");
+    assert!(!content.contains("hidden"), "{content}");
+}
@@ -0,0 +1,20 @@
+use log2::*;
+
+#[test]
+fn precision() {
+    let path = std::env::temp_dir().join("log2_precision.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).precision(TimePrecision::Micros).start();
+    info!("order 1 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let time = &content[1..content.find(']').unwrap()];
+    match time.split_once('.') {
+        Some((_, fraction)) => assert_eq!(fraction.len(), 6),
+        // microseconds since the epoch
+        None => assert_eq!(time.len(), 16),
+    }
+}
@@ -0,0 +1,25 @@
+use log2::*;
+
+#[test]
+fn preset() {
+    let path = std::env::temp_dir().join("log2_preset.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let mut log2 = log2::open(path).preset(Format::Json).start();
+    info!("order \"1\" was executed");
+    log2.flush();
+
+    log2.reconfigure(log2::open(path).preset(Format::Compact));
+    warn!("order 2 was rejected");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let lines: Vec<&str> = content.lines().collect();
+    assert!(lines[0].starts_with("{\"time\":\""));
+    assert!(lines[0].ends_with(
+        ",\"level\":\"INFO\",\"module\":\"log2_preset\",\"message\":\"order \\\"1\\\" was executed\"}"
+    ));
+    assert!(lines[1].ends_with(" W order 2 was rejected"));
+    assert!(!lines[1].contains('['));
+}
@@ -0,0 +1,26 @@
+use log2::*;
+
+#[test]
+fn reconfigure() {
+    let first = std::env::temp_dir().join("log2_reconfigure_1.txt");
+    let second = std::env::temp_dir().join("log2_reconfigure_2.txt");
+    let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+    let _ = std::fs::remove_file(first);
+    let _ = std::fs::remove_file(second);
+
+    let mut log2 = log2::open(first).start();
+    for i in 0..100 {
+        info!("order {i} was executed");
+    }
+
+    log2.reconfigure(log2::open(second).module(false).level("warn"));
+    info!("order 100 was executed");
+    warn!("order 101 was rejected");
+    log2.flush();
+
+    let content = std::fs::read_to_string(first).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 100);
+    let content = std::fs::read_to_string(second).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.ends_with("[WARN] order 101 was rejected\n"));
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "redact")]
+
+use log2::*;
+
+#[test]
+fn redact() {
+    let path = std::env::temp_dir().join("log2_redact.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .redact(r"token=\w+", "token=***")
+        .redact(r"\b(\d{4})\d{8}(\d{4})\b", "$1********$2")
+        .start();
+
+    info!("order 1 was paid by 4111111111111111 with token=abc123");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.ends_with(" order 1 was paid by 4111********1111 with token=***\n"));
+}
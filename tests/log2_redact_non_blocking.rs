@@ -0,0 +1,22 @@
+#![cfg(feature = "redact")]
+
+use log2::*;
+
+#[test]
+fn redact_non_blocking() {
+    let path = std::env::temp_dir().join("log2_redact_non_blocking.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .non_blocking(64)
+        .redact(r"token=\w+", "token=***")
+        .start();
+
+    info!("login with token=abc123");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.ends_with(" login with token=***\n"));
+    assert!(!content.contains("abc123"));
+}
@@ -18,6 +18,8 @@ fn redirect_log_file() {
     warn!("network speed is slow");
     error!("network connection was broken");
 
+    log2.flush();
+
     // Check if the file was created
     let file_path = std::path::Path::new("redirect_log.txt");
     assert!(file_path.exists(), "redirect_log.txt file was not created");
@@ -0,0 +1,26 @@
+use log2::*;
+
+#[test]
+fn restart() {
+    let first = std::env::temp_dir().join("log2_restart_1.txt");
+    let second = std::env::temp_dir().join("log2_restart_2.txt");
+    let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+    let _ = std::fs::remove_file(first);
+    let _ = std::fs::remove_file(second);
+
+    let log2 = log2::open(first).start();
+    info!("order 1 was executed");
+    drop(log2);
+
+    // starting again swaps the pipeline instead of panicking
+    let log2 = log2::open(second).start();
+    info!("order 2 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(first).expect("Failed to read the log file");
+    assert!(content.contains("order 1 was executed"));
+    assert!(!content.contains("order 2 was executed"));
+    let content = std::fs::read_to_string(second).expect("Failed to read the log file");
+    assert!(content.contains("order 2 was executed"));
+    assert!(!content.contains("order 1 was executed"));
+}
@@ -0,0 +1,23 @@
+use log2::*;
+
+#[test]
+fn rotate_on_open() {
+    let dir = std::env::temp_dir().join("log2_rotate_on_open");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "order 1 of the previous run\n").unwrap();
+
+    let log2 = log2::open(&path).rotate(3).rotate_on_open(true).start();
+
+    info!("order 2 was executed");
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 2);
+    let active = std::fs::read_to_string(&segments[0].path).unwrap();
+    assert!(!active.contains("order 1"));
+    assert!(active.contains("order 2 was executed"));
+    let previous = std::fs::read_to_string(&segments[1].path).unwrap();
+    assert_eq!(previous, "order 1 of the previous run\n");
+}
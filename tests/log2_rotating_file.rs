@@ -0,0 +1,25 @@
+use std::io::Write;
+
+#[test]
+fn rotating_file() {
+    let dir = std::env::temp_dir().join("log2_rotating_file");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("orders.csv");
+
+    let mut file = log2::RotatingFile::with(log2::open(&path).size(1024).rotate(3)).unwrap();
+    writeln!(file, "id,side,quantity").unwrap();
+    for i in 0..300 {
+        writeln!(file, "{i},buy,{}", i * 10).unwrap();
+    }
+    file.flush().unwrap();
+    assert_eq!(file.path(), path);
+    drop(file);
+
+    let active = std::fs::read_to_string(&path).unwrap();
+    assert!(active.ends_with("299,buy,2990\n"));
+    let rotated = std::fs::read_to_string(dir.join("orders.1.csv")).unwrap();
+    assert!(rotated.len() >= 1024);
+    assert!(rotated.lines().all(|line| line.split(',').count() == 3));
+    assert!(dir.join("orders.2.csv").exists());
+    assert!(!dir.join("orders.3.csv").exists());
+}
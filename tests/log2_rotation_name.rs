@@ -0,0 +1,25 @@
+use log2::*;
+
+#[test]
+fn rotation_name() {
+    let dir = std::env::temp_dir().join("log2_rotation_name");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path)
+        .size(100)
+        .rotate(3)
+        .rotation_name(|index, _| format!("app-shard7.{index}.log"))
+        .start();
+
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[1].path, dir.join("app-shard7.5.log"));
+    assert_eq!(segments[2].path, dir.join("app-shard7.4.log"));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+}
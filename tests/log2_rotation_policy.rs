@@ -0,0 +1,38 @@
+use log2::*;
+
+/// a file per batch of three orders
+struct Batches;
+
+impl RotationPolicy for Batches {
+    fn should_rotate(&self, state: &RotationState) -> bool {
+        state.records >= 3
+    }
+
+    fn next_name(&self, index: usize, _: std::time::SystemTime) -> String {
+        format!("batch-{index}.log")
+    }
+}
+
+#[test]
+fn rotation_policy() {
+    let dir = std::env::temp_dir().join("log2_rotation_policy");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path).rotate(3).rotation_policy(Batches).start();
+    for i in 1..=10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[1].path, dir.join("batch-3.log"));
+    assert_eq!(segments[2].path, dir.join("batch-2.log"));
+    let batch = std::fs::read_to_string(&segments[1].path).unwrap();
+    assert_eq!(batch.lines().count(), 3);
+    assert!(batch.ends_with("order 9 was executed\n"));
+    let active = std::fs::read_to_string(&path).unwrap();
+    assert!(active.ends_with("order 10 was executed\n"));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+}
@@ -0,0 +1,28 @@
+use log2::*;
+
+#[test]
+fn list_segments() {
+    let dir = std::env::temp_dir().join("log2_segments");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let path = path.to_str().unwrap();
+
+    let log2 = log2::open(path).size(100).rotate(3).start();
+
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let segments = log2.segments();
+    let names: Vec<_> = segments.iter().map(|s| s.path.clone()).collect();
+    assert_eq!(
+        names,
+        vec![
+            path.to_string(),
+            dir.join("log.1.txt").to_str().unwrap().to_string(),
+            dir.join("log.2.txt").to_str().unwrap().to_string(),
+        ]
+    );
+    assert!(segments[1].size >= 100);
+}
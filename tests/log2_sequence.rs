@@ -0,0 +1,26 @@
+use log2::*;
+
+#[test]
+fn sequence() {
+    let path = std::env::temp_dir().join("log2_sequence.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).sequence(true).start();
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let numbers: Vec<u64> = content
+        .lines()
+        .map(|line| {
+            let start = line.find("[#").unwrap() + 2;
+            let end = start + line[start..].find(']').unwrap();
+            line[start..end].parse().unwrap()
+        })
+        .collect();
+    assert_eq!(numbers.len(), 10);
+    assert!(numbers.windows(2).all(|n| n[1] == n[0] + 1));
+}
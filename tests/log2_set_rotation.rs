@@ -0,0 +1,27 @@
+use log2::*;
+
+#[test]
+fn set_rotation() {
+    let path = std::env::temp_dir().join("log2_set_rotation.txt");
+    let path = path.to_str().unwrap();
+    for i in 0..5 {
+        let _ = std::fs::remove_file(path.replace(".txt", &format!(".{i}.txt")));
+    }
+    let _ = std::fs::remove_file(path);
+
+    let mut log2 = log2::open(path).size(1024).rotate(2).start();
+
+    for i in 0..100 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    assert_eq!(log2.segments().len(), 2);
+
+    // keep more while investigating
+    log2.set_rotation(1024, 4);
+    for i in 100..200 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+    assert_eq!(log2.segments().len(), 4);
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+#[test]
+fn set_tee() {
+    let path = std::env::temp_dir().join("log2_set_tee.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).start();
+
+    info!("order 1 was executed");
+    log2.set_tee(true);
+    info!("order 2 was executed");
+    log2.set_tee(false);
+    info!("order 3 was executed");
+    log2.flush();
+
+    // toggling stdout never touches the file
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content.lines().count(), 3);
+}
@@ -0,0 +1,18 @@
+use log2::*;
+
+#[test]
+fn sharded_sinks() {
+    let path = std::env::temp_dir().join("log2_sharded.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let mut log2 = log2::open(path).tee(true).sharded(true).start();
+
+    info!("order was executed");
+    warn!("network speed is slow");
+    log2.stop();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("[INFO] [log2_sharded] order was executed"));
+    assert!(content.contains("[WARN] [log2_sharded] network speed is slow"));
+}
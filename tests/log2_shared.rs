@@ -0,0 +1,55 @@
+use log2::*;
+
+#[test]
+fn shared() {
+    let dir = std::env::temp_dir().join("log2_shared");
+    let path = dir.join("log.txt");
+
+    // the same test binary runs as the writing processes
+    if let Ok(id) = std::env::var("LOG2_SHARED_CHILD") {
+        let _log2 = log2::open(&path)
+            .size(4096)
+            .rotate(100)
+            .shared(true)
+            .start();
+        for i in 0..500 {
+            info!("process {id} wrote order {i}");
+        }
+        return;
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let children: Vec<_> = (0..3)
+        .map(|id| {
+            std::process::Command::new(std::env::current_exe().unwrap())
+                .args(["shared", "--exact", "--nocapture"])
+                .env("LOG2_SHARED_CHILD", id.to_string())
+                .stdout(std::process::Stdio::null())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let mut orders = Vec::new();
+    let mut files = 0;
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "lock") {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        files += 1;
+        for line in content.lines() {
+            let (_, order) = line.split_once("process ").unwrap();
+            orders.push(order.to_string());
+        }
+    }
+    assert!(files > 2);
+    orders.sort();
+    orders.dedup();
+    assert_eq!(orders.len(), 1500);
+}
@@ -0,0 +1,21 @@
+use log2::*;
+
+static HANDLE: std::sync::OnceLock<std::sync::Mutex<Handle>> = std::sync::OnceLock::new();
+
+#[test]
+fn shutdown() {
+    let path = std::env::temp_dir().join("log2_shutdown.txt");
+    let _ = std::fs::remove_file(&path);
+
+    // owned by a static, never dropped
+    HANDLE.get_or_init(|| std::sync::Mutex::new(log2::open(&path).start()));
+    for i in 0..1000 {
+        info!("order {i} was executed");
+    }
+    assert!(log2::shutdown(std::time::Duration::from_secs(5)));
+    info!("order after the shutdown");
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("order 999 was executed"));
+    assert!(!text.contains("after the shutdown"));
+}
@@ -0,0 +1,33 @@
+use log2::*;
+
+#[test]
+fn stats() {
+    let path = std::env::temp_dir().join("log2_stats.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(path.replace(".txt", ".1.txt"));
+
+    let log2 = log2::open(path).size(1024).rotate(2).start();
+
+    for i in 0..20 {
+        info!("order {i} was executed");
+    }
+    warn!("order 20 was rejected");
+    error!("order 21 failed");
+    trace!("order 22 traced");
+    log2.flush();
+
+    let stats = log2.stats();
+    assert_eq!(stats.info, 20);
+    assert_eq!(stats.warn, 1);
+    assert_eq!(stats.error, 1);
+    assert_eq!(stats.trace, 1);
+    assert_eq!(stats.debug, 0);
+    assert_eq!(stats.queued, 0);
+    assert_eq!(stats.dropped, 0);
+    assert!(stats.rotations >= 1);
+
+    let written: u64 = log2.segments().iter().map(|s| s.size).sum();
+    assert!(stats.bytes > 0);
+    assert!(stats.bytes >= written);
+}
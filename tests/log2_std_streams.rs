@@ -0,0 +1,37 @@
+#![cfg(unix)]
+
+use log2::*;
+use std::io::Write;
+
+#[test]
+fn std_streams() {
+    let dir = std::env::temp_dir().join("log2_std_streams");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path).capture_std_streams(true).start();
+    info!("daemon was started");
+    // the print macros are caught by the test harness, not the descriptors
+    std::io::stdout().write_all(b"stray print\n").unwrap();
+    std::io::stderr().write_all(b"library warning\n").unwrap();
+    std::process::Command::new("sh")
+        .args(["-c", "echo from child"])
+        .status()
+        .unwrap();
+
+    let mut content = String::new();
+    for _ in 0..100 {
+        log2.flush();
+        content = std::fs::read_to_string(&path).unwrap_or_default();
+        if content.contains("from child") {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(content.contains("[INFO] [stdout] stray print"), "{content}");
+    assert!(
+        content.contains("[WARN] [stderr] library warning"),
+        "{content}"
+    );
+    assert!(content.contains("from child"), "{content}");
+}
@@ -0,0 +1,23 @@
+use log2::*;
+
+#[test]
+fn strip_ansi() {
+    let path = std::env::temp_dir().join("log2_strip_ansi.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let colored = |line: &mut String, parts: &Parts| {
+        *line += &format!("\x1b[1;32m{}\x1b[0m {}", parts.level, parts.message);
+    };
+    let log2 = log2::open(path)
+        .tee(true)
+        .format(colored)
+        .strip_ansi(true)
+        .start();
+
+    info!("order 1 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content, "INFO order 1 was executed\n");
+}
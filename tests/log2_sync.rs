@@ -0,0 +1,24 @@
+use log2::*;
+
+#[test]
+fn sync() {
+    let path = std::env::temp_dir().join("log2_sync.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(path.replace(".txt", ".1.txt"));
+
+    let log2 = log2::open(path).sync(true).size(1024).rotate(2).start();
+
+    info!("order 1 was executed");
+
+    // visible without a flush
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.ends_with("order 1 was executed\n"));
+
+    for i in 2..100 {
+        info!("order {i} was executed");
+    }
+    assert!(log2.stats().rotations > 0);
+    assert_eq!(log2.queue_len(), 0);
+    assert!(log2.healthy());
+}
@@ -0,0 +1,35 @@
+use log2::*;
+
+// the test runs itself again as a child process to read what reaches stdout
+#[test]
+fn sync_report() {
+    let dir = std::env::temp_dir().join("log2_sync_report");
+
+    if std::env::var("LOG2_SYNC_CHILD").is_ok() {
+        let path = dir.join("log.txt");
+        let _log2 = log2::open(path.to_str().unwrap())
+            .sync(true)
+            .tee(true)
+            .module(true)
+            .size(64)
+            .start();
+        // the rotation can't find the file anymore
+        std::fs::remove_dir_all(&dir).unwrap();
+        for i in 0..3 {
+            info!("order {i} was executed");
+        }
+        return;
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["sync_report", "--exact", "--nocapture"])
+        .env("LOG2_SYNC_CHILD", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[ERROR] [log2::internal] failed to write a record: "));
+    assert!(!stdout.contains("error: "));
+}
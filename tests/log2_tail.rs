@@ -0,0 +1,17 @@
+use log2::*;
+
+#[test]
+fn tail() {
+    let log2 = log2::stdout().module(false).recent(5).start();
+
+    for i in 0..20 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let lines = log2.tail(3);
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with("[INFO] order 17 was executed"));
+    assert!(lines[2].ends_with("[INFO] order 19 was executed"));
+    assert_eq!(log2.tail(100).len(), 5);
+}
@@ -0,0 +1,27 @@
+use log2::*;
+
+#[test]
+fn tee_format() {
+    let path = std::env::temp_dir().join("log2_tee_format.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let console = |line: &mut String, parts: &Parts| {
+        *line += &format!("console {}", parts.message);
+    };
+    let plain = |line: &mut String, parts: &Parts| {
+        *line += &format!("{} {}", parts.level, parts.message);
+    };
+
+    let log2 = log2::open(path)
+        .tee(true)
+        .tee_format(console)
+        .file_format(plain)
+        .start();
+
+    info!("order 1 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert_eq!(content, "INFO order 1 was executed\n");
+}
@@ -0,0 +1,57 @@
+use log2::*;
+
+#[test]
+fn tee_level() {
+    let path = std::env::temp_dir().join("log2_tee_level.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .tee(true)
+        .level("info")
+        .tee_level("error")
+        .file_level("debug")
+        .start();
+
+    debug!("order 1 was executed");
+    trace!("order 2 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("order 1 was executed"));
+    assert!(!content.contains("order 2 was executed"));
+}
+
+// the test runs itself again as a child process to read what reaches stdout
+#[test]
+fn tee_level_stdout() {
+    let path = std::env::temp_dir().join("log2_tee_level_stdout.txt");
+
+    if std::env::var("LOG2_TEE_CHILD").is_ok() {
+        let log2 = log2::open(path.to_str().unwrap())
+            .tee(true)
+            .level("info")
+            .file_level("trace")
+            .start();
+        debug!("order 1 was executed");
+        info!("order 2 was executed");
+        log2.flush();
+        return;
+    }
+
+    let _ = std::fs::remove_file(&path);
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["tee_level_stdout", "--exact", "--nocapture"])
+        .env("LOG2_TEE_CHILD", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // the console keeps the global level, the file goes below it
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("order 1 was executed"));
+    assert!(stdout.contains("order 2 was executed"));
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.contains("order 1 was executed"));
+    assert!(content.contains("order 2 was executed"));
+}
@@ -0,0 +1,28 @@
+use log2::*;
+
+#[test]
+fn template() {
+    let dir = std::env::temp_dir().join("log2_template");
+    let _ = std::fs::remove_dir_all(&dir);
+    let template = dir.join("%Y/app-{pid}.log");
+
+    let log2 = log2::template(template.to_str().unwrap()).start();
+
+    info!("order 1 was executed");
+    log2.flush();
+
+    let segments = log2.segments();
+    let path = &segments[0].path;
+    let name = format!("app-{}.log", std::process::id());
+    assert_eq!(path.file_name().unwrap().to_str().unwrap(), name);
+    let year = path
+        .parent()
+        .unwrap()
+        .file_name()
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(year.len() == 4 && year.chars().all(|c| c.is_ascii_digit()));
+    let content = std::fs::read_to_string(path).unwrap();
+    assert!(content.contains("order 1 was executed"));
+}
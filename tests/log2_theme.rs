@@ -0,0 +1,24 @@
+use log2::*;
+
+#[test]
+fn theme() {
+    let path = std::env::temp_dir().join("log2_theme.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path)
+        .tee(true)
+        .theme(Theme {
+            error: Color::Red.bold(),
+            time: Color::Rgb(0x5f, 0x5f, 0x5f).normal(),
+            ..Default::default()
+        })
+        .start();
+
+    error!("order 1 was broken");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("[ERROR] [log2_theme] order 1 was broken"));
+    assert!(!content.contains('\x1b'));
+}
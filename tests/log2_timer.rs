@@ -0,0 +1,22 @@
+use log2::*;
+
+#[test]
+fn timer_guard() {
+    let path = std::env::temp_dir().join("log2_timer.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).start();
+
+    {
+        let _t = log2::timer!("load_orders");
+        let _d = log2::timer!(level::Debug, "load_prices");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(content.contains("[INFO] [log2_timer] load_orders took"));
+    assert!(content.contains("[DEBUG] [log2_timer] load_prices took"));
+    assert!(!content.contains("(line"), "{content}");
+}
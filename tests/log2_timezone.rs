@@ -0,0 +1,22 @@
+#![cfg(feature = "timezone")]
+
+use log2::*;
+
+#[test]
+fn timezone() {
+    let path = std::env::temp_dir().join("log2_timezone.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).timezone("Asia/Kolkata").start();
+    info!("order 1 was executed");
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    let time = &content[1..content.find(']').unwrap()];
+    let time = chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%.3f").unwrap();
+
+    // five and a half hours ahead of utc, no daylight saving
+    let kolkata = chrono::Utc::now().naive_utc() + chrono::Duration::minutes(330);
+    assert!((kolkata - time).num_seconds().abs() < 60);
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "tokio")]
+use log2::*;
+
+#[test]
+fn tokio_task() {
+    let path = std::env::temp_dir().join("log2_tokio.txt");
+    let path = path.to_str().unwrap().to_string();
+    let _ = std::fs::remove_file(&path);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let log2 = log2::open(&path).size(100).rotate(2).start_tokio();
+        for i in 0..10 {
+            info!("order {i} was executed");
+        }
+        log2.flush_async().await;
+
+        let segments = log2.segments();
+        assert_eq!(segments.len(), 2);
+        let content =
+            std::fs::read_to_string(&segments[1].path).expect("Failed to read the log file");
+        assert!(content.contains("order 9 was executed"));
+    });
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "tokio")]
+use log2::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn tokio_blocking() {
+    let dir = std::env::temp_dir().join("log2_tokio_blocking");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let pruned = Arc::new(AtomicUsize::new(0));
+    let count = pruned.clone();
+    runtime.block_on(async {
+        let log2 = log2::open(&path)
+            .size(1024)
+            .rotate(2)
+            .shared(true)
+            .on_prune(move |_| {
+                count.fetch_add(1, Ordering::Relaxed);
+                Decision::Delete
+            })
+            .start_tokio();
+        for i in 0..100 {
+            info!("order {i} was executed");
+        }
+        log2.flush_async().await;
+    });
+
+    assert!(pruned.load(Ordering::Relaxed) > 0);
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.contains("order 99 was executed"));
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "tokio")]
+use log2::*;
+
+#[test]
+fn tokio_stop() {
+    let path = std::env::temp_dir().join("log2_tokio_stop.txt");
+    let path = path.to_str().unwrap().to_string();
+    let _ = std::fs::remove_file(&path);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let mut log2 = log2::open(&path)
+            .flush_interval(std::time::Duration::from_secs(3600))
+            .start_tokio();
+        for i in 0..1000 {
+            info!("order {i} was executed");
+        }
+        log2.stop_async().await;
+        assert!(!log2.healthy());
+    });
+    drop(runtime);
+
+    let content = std::fs::read_to_string(&path).expect("Failed to read the log file");
+    assert!(content.ends_with("order 999 was executed\n"));
+}
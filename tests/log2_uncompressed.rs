@@ -0,0 +1,32 @@
+#![cfg(not(any(feature = "gzip", feature = "lz4")))]
+
+use log2::*;
+
+#[test]
+fn uncompressed() {
+    let dir = std::env::temp_dir().join("log2_uncompressed");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let log2 = log2::open(&path)
+        .size(1000)
+        .rotate(2)
+        .compress(true)
+        .start();
+    for i in 0..20 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let names = log2
+        .segments()
+        .into_iter()
+        .map(|s| s.path)
+        .collect::<Vec<_>>();
+    assert!(names.contains(&dir.join("log.1.txt")));
+    let content = names
+        .iter()
+        .map(|name| std::fs::read_to_string(name).unwrap())
+        .collect::<String>();
+    assert!(content.contains("compress() needs the gzip or lz4 feature"));
+}
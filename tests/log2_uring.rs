@@ -0,0 +1,20 @@
+#![cfg(feature = "io-uring")]
+use log2::*;
+
+#[test]
+fn io_uring_writes() {
+    let path = std::env::temp_dir().join("log2_uring.txt");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+
+    let log2 = log2::open(path).io_uring(true).buffer(0).start();
+    for i in 0..100 {
+        info!("order {i} was executed");
+    }
+    log2.flush();
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    for i in 0..100 {
+        assert!(content.contains(&format!("order {i} was executed\n")));
+    }
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "watch")]
+
+use log2::*;
+
+#[test]
+fn watch() {
+    let dir = std::env::temp_dir().join("log2_watch");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("log.txt");
+    let path = path.to_str().unwrap();
+    let config = dir.join("log2.toml");
+    let config = config.to_str().unwrap();
+
+    std::fs::write(config, format!("path = '{path}'\nlevel = 'warn'\n")).unwrap();
+    let mut log2 = log2::from_config(config).unwrap().start();
+    log2.watch(config).unwrap();
+
+    info!("order 1 was executed");
+    log2.flush();
+
+    // bump the verbosity without a restart
+    std::fs::write(config, format!("path = '{path}'\nlevel = 'info'\n")).unwrap();
+    let mut applied = false;
+    for _ in 0..100 {
+        info!("order 2 was executed");
+        log2.flush();
+        let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+        if content.contains("order 2 was executed") {
+            applied = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert!(applied);
+
+    let content = std::fs::read_to_string(path).expect("Failed to read the log file");
+    assert!(!content.contains("order 1 was executed"));
+}
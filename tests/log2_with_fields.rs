@@ -0,0 +1,19 @@
+#[test]
+fn with_fields() {
+    let path = std::env::temp_dir().join("log2_with_fields.txt");
+    let _ = std::fs::remove_file(&path);
+
+    let mut log2 = log2::open(&path).start();
+    let conn = log2::with_fields(module_path!(), [("conn", 7)]);
+    conn.info(format_args!("order 1 was received"));
+    let order = conn.with_fields([("order", "A-2")]);
+    order.warn(format_args!("order 2 is late"));
+    let db = log2::logger("db").with_fields([("pool", "main")]);
+    db.child("query").info(format_args!("order 3 was queried"));
+    log2.stop();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("[log2_with_fields] order 1 was received conn=7\n"));
+    assert!(text.contains("[log2_with_fields] order 2 is late conn=7 order=A-2\n"));
+    assert!(text.contains("[db.query] order 3 was queried pool=main\n"));
+}
@@ -0,0 +1,24 @@
+use log2::*;
+
+#[test]
+fn write_line() {
+    let dir = std::env::temp_dir().join("log2_write_line");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+
+    let mut log2 = log2::open(&path).size(1024).rotate(2).start();
+    info!("order was received");
+    log2.write_line("engine: order matched");
+    for i in 0..100 {
+        log2.write_line(&format!("engine: tick {i}\n"));
+    }
+    log2.stop();
+
+    let rotated = std::fs::read_to_string(dir.join("log.1.txt")).unwrap();
+    let lines: Vec<_> = rotated.lines().collect();
+    assert!(lines[0].ends_with("order was received"));
+    assert_eq!(lines[1], "engine: order matched");
+    assert_eq!(lines[2], "engine: tick 0");
+    let active = std::fs::read_to_string(&path).unwrap();
+    assert!(active.ends_with("engine: tick 99\n"));
+}
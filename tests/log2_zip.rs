@@ -0,0 +1,25 @@
+#![cfg(feature = "zip")]
+use log2::*;
+
+#[test]
+fn export_zip() {
+    let dir = std::env::temp_dir().join("log2_zip");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("log.txt");
+    let archive = dir.join("diagnostics.zip");
+
+    let log2 = log2::open(path.to_str().unwrap())
+        .size(100)
+        .rotate(3)
+        .start();
+    for i in 0..10 {
+        info!("order {i} was executed");
+    }
+    log2.export_zip(archive.to_str().unwrap()).unwrap();
+
+    let data = std::fs::read(&archive).expect("Failed to read the archive");
+    assert!(data.starts_with(b"PK"));
+    for name in ["log.txt", "log.1.txt", "log.2.txt"] {
+        assert!(data.windows(name.len()).any(|w| w == name.as_bytes()));
+    }
+}